@@ -1,5 +1,17 @@
-use rusqlite::{Connection, Row};
-use std::{fs, io};
+use crate::{
+    diff::{self, DbDiff},
+    error::AppError,
+    schema::{self, SchemaChange},
+    value::Value,
+};
+use rusqlite::{fallible_iterator::FallibleIterator, Batch, Connection, OpenFlags, Row};
+use std::{
+    collections::HashMap,
+    fs, io,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::SystemTime,
+};
 
 const FILE_PATHS: [&str; 3] = [".db", ".sqlite3", ".db3"];
 
@@ -7,99 +19,1151 @@ const SQL_TABLES: &str = "
     SELECT type,name,sql,tbl_name FROM main.sqlite_master;
 ";
 
+/// Name the results of a query are shown under.
+const QUERY_NAME: &str = "Query";
+
 #[derive(Debug, Default)]
 pub struct Db {
     pub path: String,
     pub tables: Vec<Table>,
     pub views: Vec<Table>,
+    /// The last query run that returned rows
+    pub query: Option<Table>,
 }
 
 #[derive(Debug, Default)]
 pub struct Table {
     pub name: String,
     pub sql: String,
+    /// True for ordinary tables that can be paged by rowid
+    pub has_rowid: bool,
+    /// True for the results of a query rather than a table or view
+    pub is_query: bool,
+    /// Indexes of a table, empty for views and queries
+    pub indexes: Vec<Index>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Index {
+    pub name: String,
+    /// None for the automatic indexes of `UNIQUE` and `PRIMARY KEY`
+    /// constraints, these can't be dropped on their own
+    pub sql: Option<String>,
+}
+
+impl Table {
+    /// The results of a query, browsed like a view.
+    pub fn query(sql: &str) -> Table {
+        Table {
+            name: QUERY_NAME.to_string(),
+            sql: sql.trim().trim_end_matches(';').trim_end().to_string(),
+            has_rowid: false,
+            is_query: true,
+            indexes: Vec::new(),
+        }
+    }
+
+    /// What rows are selected from, the quoted name or the query as a
+    /// subquery.
+    pub fn source(&self) -> String {
+        match self.is_query {
+            // on separate lines so a trailing comment can't swallow the paren
+            true => format!("(\n{}\n)", self.sql),
+            false => quote_ident(&self.name),
+        }
+    }
+}
+
+/// Which rows of a table are browsed and in what order, set from the
+/// Browse tab.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    /// Expression the rows must match, as written after `WHERE`
+    pub condition: Option<String>,
+    /// Column the rows are sorted by, and true for descending order
+    pub order: Option<(String, bool)>,
+}
+
+impl Filter {
+    pub fn is_empty(&self) -> bool {
+        self.condition.is_none() && self.order.is_none()
+    }
+
+    /// Rows sorted by a column are paged by OFFSET, like views.
+    pub fn pages_by_key(&self, table: &Table) -> bool {
+        table.has_rowid && self.order.is_none()
+    }
+
+    /// The `WHERE` clause of the condition and any further terms, empty if
+    /// there are none.
+    fn where_sql(&self, terms: &[String]) -> String {
+        let mut terms = terms.to_vec();
+        if let Some(condition) = &self.condition {
+            // on its own line so a trailing comment can't swallow the rest
+            terms.push(format!("(\n{condition}\n)"));
+        }
+        match terms.is_empty() {
+            true => String::new(),
+            false => format!(" WHERE {}", terms.join(" AND ")),
+        }
+    }
+
+    /// The `ORDER BY` terms of the sort, None if unsorted.
+    fn order_sql(&self) -> Option<String> {
+        self.order.as_ref().map(|(column, descending)| {
+            let direction = if *descending { " DESC" } else { "" };
+            format!("{}{direction}", quote_ident(column))
+        })
+    }
+}
+
+/// Notices when the database changes on disk, through `PRAGMA data_version`
+/// of a connection kept open and the modification times of the file and its
+/// write-ahead log.
+pub struct Watcher {
+    path: String,
+    con: Connection,
+    data_version: i64,
+    modified: [Option<SystemTime>; 2],
+}
+
+impl Watcher {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// True if another connection committed, or the files were written,
+    /// since the last call.
+    pub fn changed(&mut self) -> Result<bool, AppError> {
+        let data_version = self
+            .con
+            .pragma_query_value(None, "data_version", |r| r.get(0))?;
+        let modified = modified_times(&self.path);
+        let changed = data_version != self.data_version || modified != self.modified;
+        self.data_version = data_version;
+        self.modified = modified;
+        Ok(changed)
+    }
+}
+
+/// When the database file and its write-ahead log were last written, None
+/// for files that don't exist.
+fn modified_times(path: &str) -> [Option<SystemTime>; 2] {
+    [path.to_string(), format!("{path}-wal")]
+        .map(|file| fs::metadata(file).and_then(|m| m.modified()).ok())
+}
+
+/// What running a statement did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryOutcome {
+    /// The statement is a query, its rows are browsed through [`Db::query`]
+    Rows,
+    /// The statement was run right away and returned these columns and
+    /// rows, e.g. a PRAGMA or an INSERT with a RETURNING clause
+    Returned(Vec<String>, Vec<Vec<Value>>),
+    /// The statement changed the given number of rows
+    Changed(usize),
+}
+
+/// Where a page of rows starts.
+///
+/// Tables with a rowid are paged by key ranges so the cost of a page does not
+/// grow with its depth, views and `WITHOUT ROWID` tables fall back to OFFSET.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seek {
+    /// Skip the given number of rows.
+    Offset(usize),
+    /// Rows following the given rowid.
+    After(i64),
+    /// Rows preceding the given rowid.
+    Before(i64),
+    /// The last rows of the table.
+    Last,
+}
+
+/// A page of rows selected from a table.
+#[derive(Debug, Default)]
+pub struct Page {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// Rowid of each row, empty when the table is not paged by rowid
+    pub keys: Vec<i64>,
+}
+
+/// A step of a query plan as reported by `EXPLAIN QUERY PLAN`, steps form
+/// a tree through their parent id.
+#[derive(Debug, Clone)]
+pub struct PlanStep {
+    pub id: i64,
+    /// Id of the parent step, 0 for top level steps
+    pub parent: i64,
+    pub detail: String,
+}
+
+/// Facts about a database file, read from its header through PRAGMAs.
+#[derive(Debug, Default)]
+pub struct Overview {
+    /// Size of the database file in bytes, without its journal or WAL
+    pub file_size: u64,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+    pub encoding: String,
+    pub user_version: i64,
+    pub application_id: i64,
+    pub journal_mode: String,
+    pub auto_vacuum: String,
+    pub sqlite_version: String,
+    /// Bytes used by each table and its indexes according to `dbstat`,
+    /// empty when sqlite was built without it
+    pub table_sizes: HashMap<String, u64>,
+}
+
+/// Space used by a table or index, summed over the pages of its b-tree.
+#[derive(Debug, Clone, Default)]
+pub struct SpaceUsage {
+    pub name: String,
+    /// Table the object belongs to, its own name for tables
+    pub table: String,
+    pub is_index: bool,
+    pub pages: u64,
+    /// Bytes of all pages
+    pub bytes: u64,
+    /// Bytes holding keys and row data
+    pub payload: u64,
+    /// Bytes of pages not holding anything
+    pub unused: u64,
+    /// Pages not directly following the page before them in the b-tree
+    pub gaps: u64,
+}
+
+impl SpaceUsage {
+    /// Percentage of pages out of order, as `sqlite3_analyzer` reports it.
+    pub fn fragmentation(&self) -> f64 {
+        percent(self.gaps, self.pages)
+    }
+
+    /// Percentage of the bytes used by page and cell headers and free
+    /// space rather than payload.
+    pub fn overhead(&self) -> f64 {
+        percent(self.bytes - self.payload, self.bytes)
+    }
+}
+
+/// Part of a whole in percent, 0 for an empty whole.
+pub fn percent(part: u64, whole: u64) -> f64 {
+    match whole {
+        0 => 0.0,
+        _ => part as f64 * 100.0 / whole as f64,
+    }
+}
+
+/// Checks and upkeep that can be run on a database.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Maintenance {
+    IntegrityCheck,
+    QuickCheck,
+    ForeignKeyCheck,
+    Vacuum,
+    /// Write a vacuumed copy to the given path
+    VacuumInto(String),
+    Analyze,
+    Reindex,
+    Optimize,
+}
+
+impl Maintenance {
+    /// True for checks, which only read the database and report problems.
+    pub fn is_check(&self) -> bool {
+        matches!(
+            self,
+            Maintenance::IntegrityCheck | Maintenance::QuickCheck | Maintenance::ForeignKeyCheck
+        )
+    }
 }
 
+/// What running a [`Maintenance`] task found or did.
+#[derive(Debug, Default)]
+pub struct MaintenanceReport {
+    /// Problems found by a check, one per line
+    pub lines: Vec<String>,
+    /// File size before and after a task that writes, for `VACUUM INTO`
+    /// the size of the copy
+    pub sizes: Option<(u64, u64)>,
+}
+
+/// Where the value of a PRAGMA lives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PragmaScope {
+    /// Set per connection, kept by the app and applied to each connection
+    Connection,
+    /// Stored in the database file
+    Database,
+    /// Can only be read
+    ReadOnly,
+}
+
+/// A PRAGMA shown in the PRAGMA panel.
+#[derive(Debug)]
+pub struct PragmaInfo {
+    pub name: &'static str,
+    pub scope: PragmaScope,
+    pub description: &'static str,
+}
+
+const fn pragma(name: &'static str, scope: PragmaScope, description: &'static str) -> PragmaInfo {
+    PragmaInfo {
+        name,
+        scope,
+        description,
+    }
+}
+
+/// The PRAGMAs that can be inspected, and changed unless read only.
+pub const PRAGMAS: [PragmaInfo; 17] = [
+    pragma(
+        "journal_mode",
+        PragmaScope::Database,
+        "How changes are journaled: delete, truncate, persist, memory, wal or off, the file only keeps wal",
+    ),
+    pragma(
+        "synchronous",
+        PragmaScope::Connection,
+        "How often to wait for writes to reach the disk: 0 off, 1 normal, 2 full, 3 extra",
+    ),
+    pragma(
+        "foreign_keys",
+        PragmaScope::Connection,
+        "Enforce foreign key constraints: 0 or 1",
+    ),
+    pragma(
+        "cache_size",
+        PragmaScope::Connection,
+        "Pages kept in memory, negative values are a size in KiB",
+    ),
+    pragma(
+        "busy_timeout",
+        PragmaScope::Connection,
+        "Milliseconds to wait for a lock held by another connection",
+    ),
+    pragma(
+        "temp_store",
+        PragmaScope::Connection,
+        "Where temporary tables and indexes live: 0 default, 1 file, 2 memory",
+    ),
+    pragma(
+        "mmap_size",
+        PragmaScope::Connection,
+        "Bytes of the file to access through memory mapping, 0 disables it",
+    ),
+    pragma(
+        "recursive_triggers",
+        PragmaScope::Connection,
+        "Let triggers fire other triggers recursively: 0 or 1",
+    ),
+    pragma(
+        "secure_delete",
+        PragmaScope::Connection,
+        "Overwrite deleted content with zeros: 0, 1 or fast",
+    ),
+    pragma(
+        "wal_autocheckpoint",
+        PragmaScope::Connection,
+        "Pages in the WAL that trigger a checkpoint, 0 disables it",
+    ),
+    pragma(
+        "user_version",
+        PragmaScope::Database,
+        "Version number free for the application to use",
+    ),
+    pragma(
+        "application_id",
+        PragmaScope::Database,
+        "Number identifying the application the file belongs to",
+    ),
+    pragma(
+        "auto_vacuum",
+        PragmaScope::Database,
+        "Give free pages back to the file system: 0 none, 1 full, 2 incremental, applies after VACUUM",
+    ),
+    pragma(
+        "page_size",
+        PragmaScope::Database,
+        "Bytes per page, a power of two from 512 to 65536, applies after VACUUM",
+    ),
+    pragma(
+        "encoding",
+        PragmaScope::ReadOnly,
+        "Text encoding, fixed when the database is created",
+    ),
+    pragma(
+        "page_count",
+        PragmaScope::ReadOnly,
+        "Pages in the database file",
+    ),
+    pragma(
+        "freelist_count",
+        PragmaScope::ReadOnly,
+        "Unused pages in the database file",
+    ),
+];
+
+/// Row counts of several tables as they are done, each along with its
+/// [`count_sql`].
+pub type TableCounts = Receiver<(String, Result<usize, AppError>)>;
+
+/// Number of rows in a table, either counted or estimated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowCount {
+    Exact(usize),
+    /// Estimate from `sqlite_stat1` or the largest rowid
+    Estimate(usize),
+}
+
+impl RowCount {
+    pub fn value(&self) -> usize {
+        match self {
+            RowCount::Exact(n) | RowCount::Estimate(n) => *n,
+        }
+    }
+}
+
+/// Rows paired with their rowid.
+type KeyedRows = Vec<(i64, Vec<Value>)>;
+
 #[derive(Debug, Default)]
 pub struct App {
     pub current_db: Option<Db>,
+    /// Open databases read-only, nothing can be changed
+    pub read_only: bool,
+    /// Connection PRAGMAs set by the user, applied to every connection
+    pub settings: Vec<(String, String)>,
+    /// Short message shown in the status line of the outer frame
+    pub status: Option<String>,
 }
 
 impl App {
     /// Load at database at a given path
-    pub fn load_db(&mut self, path: &str) -> Result<(), rusqlite::Error> {
-        let con = Connection::open(path)?;
+    pub fn load_db(&mut self, path: &str) -> Result<(), AppError> {
+        let con = open(path, self.read_only, &self.settings)?;
         let (tables, views) = get_tables(&con)?;
 
         self.current_db = Some(Db {
             path: path.to_string(),
             tables,
             views,
+            query: None,
         });
         Ok(())
     }
 
+    /// Read the tables and views of the current database again, keeping the
+    /// results of the last query.
+    pub fn reload_db(&mut self) -> Result<(), AppError> {
+        let db = self.current_db.as_mut().ok_or(AppError::NoDatabase)?;
+        let con = open(&db.path, self.read_only, &self.settings)?;
+        (db.tables, db.views) = get_tables(&con)?;
+        Ok(())
+    }
+
+    /// Start watching the current database for changes, see [`Watcher`].
+    pub fn watch(&self) -> Result<Watcher, AppError> {
+        let db = self.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        let con = self.connection()?;
+        let data_version = con.pragma_query_value(None, "data_version", |r| r.get(0))?;
+        Ok(Watcher {
+            path: db.path.clone(),
+            con,
+            data_version,
+            modified: modified_times(&db.path),
+        })
+    }
+
+    /// Header facts and table sizes of the current database.
+    pub fn overview(&self) -> Result<Overview, AppError> {
+        let db = self.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        let con = self.connection()?;
+        let int = |name: &str| con.pragma_query_value(None, name, |r| r.get::<_, i64>(0));
+        let text = |name: &str| con.pragma_query_value(None, name, |r| r.get::<_, String>(0));
+        let auto_vacuum = match int("auto_vacuum")? {
+            0 => "none".to_string(),
+            1 => "full".to_string(),
+            2 => "incremental".to_string(),
+            n => n.to_string(),
+        };
+        Ok(Overview {
+            file_size: fs::metadata(&db.path)?.len(),
+            page_size: int("page_size")?,
+            page_count: int("page_count")?,
+            freelist_count: int("freelist_count")?,
+            encoding: text("encoding")?,
+            user_version: int("user_version")?,
+            application_id: int("application_id")?,
+            journal_mode: text("journal_mode")?,
+            auto_vacuum,
+            sqlite_version: rusqlite::version().to_string(),
+            table_sizes: HashMap::default(),
+        })
+    }
+
+    /// Read the sizes of the tables of the overview on a separate connection
+    /// and thread, they take a walk over every page.
+    pub fn table_sizes_in_background(&self) -> Result<Receiver<HashMap<String, u64>>, AppError> {
+        let db = self.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        let path = db.path.clone();
+        let read_only = self.read_only;
+        let settings = self.settings.clone();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            // sizes are a nice to have, not worth an error
+            let sizes = open(&path, read_only, &settings)
+                .and_then(|con| table_sizes(&con))
+                .unwrap_or_default();
+            let _ = tx.send(sizes);
+        });
+        Ok(rx)
+    }
+
+    /// Space used by every table and index, from a walk over all pages with
+    /// `dbstat`.
+    pub fn space_usage(&self) -> Result<Vec<SpaceUsage>, AppError> {
+        let con = self.connection()?;
+        // pages come in the order the b-tree is walked
+        let mut stmt = con.prepare(
+            "SELECT s.name, coalesce(m.tbl_name, s.name), m.type = 'index',
+                    s.pageno, s.pgsize, s.payload, s.unused
+             FROM dbstat s LEFT JOIN sqlite_master m ON m.name = s.name;",
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut usage: Vec<SpaceUsage> = Vec::new();
+        // position in usage and last page seen of each object
+        let mut seen: HashMap<String, (usize, i64)> = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let name: String = row.get(0)?;
+            let page: i64 = row.get(3)?;
+            let i = match seen.get_mut(&name) {
+                Some((i, last)) => {
+                    if page != *last + 1 {
+                        usage[*i].gaps += 1;
+                    }
+                    *last = page;
+                    *i
+                }
+                None => {
+                    usage.push(SpaceUsage {
+                        name: name.clone(),
+                        table: row.get(1)?,
+                        is_index: row.get::<_, Option<bool>>(2)?.unwrap_or(false),
+                        ..SpaceUsage::default()
+                    });
+                    seen.insert(name, (usage.len() - 1, page));
+                    usage.len() - 1
+                }
+            };
+            let entry = &mut usage[i];
+            entry.pages += 1;
+            entry.bytes += row.get::<_, i64>(4)? as u64;
+            entry.payload += row.get::<_, i64>(5)? as u64;
+            entry.unused += row.get::<_, i64>(6)? as u64;
+        }
+        Ok(usage)
+    }
+
     /// Select a page of rows from a given Table.
+    ///
+    /// Only the given columns are selected, or all of them if none are given.
     pub fn select(
         &self,
         table: &Table,
+        columns: &[String],
+        filter: &Filter,
         limit: usize,
-        offset: usize,
-    ) -> Result<(Vec<String>, Vec<Vec<String>>), rusqlite::Error> {
+        seek: Seek,
+    ) -> Result<Page, AppError> {
+        let sql = page_sql(table, columns, filter, limit, seek);
+        if !table.has_rowid {
+            let (columns, rows) = self.query_rows(&sql, 0)?;
+            return Ok(Page {
+                columns,
+                rows: rows.into_iter().map(|(_, row)| row).collect(),
+                keys: Vec::new(),
+            });
+        }
+
+        let (columns, mut rows) = self.query_rows(&sql, 1)?;
+        if filter.pages_by_key(table) && matches!(seek, Seek::Before(_) | Seek::Last) {
+            rows.reverse();
+        }
+        let (keys, rows) = rows.into_iter().unzip();
+        Ok(Page {
+            columns,
+            rows,
+            keys,
+        })
+    }
+
+    /// The plan sqlite chose for a query.
+    pub fn explain(&self, sql: &str) -> Result<Vec<PlanStep>, AppError> {
+        let con = self.connection()?;
+        let mut stmt = con.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+        let steps = stmt
+            .query_map([], |row| {
+                Ok(PlanStep {
+                    id: row.get(0)?,
+                    parent: row.get(1)?,
+                    detail: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(steps)
+    }
+
+    /// Run a query handing its column names to `start`, then each row to
+    /// `f` without keeping them, for output too large to hold.
+    pub fn stream_rows<T>(
+        &self,
+        sql: &str,
+        start: impl FnOnce(Vec<String>) -> Result<T, AppError>,
+        mut f: impl FnMut(&mut T, Vec<Value>) -> Result<(), AppError>,
+    ) -> Result<T, AppError> {
+        let con = self.connection()?;
+        let mut stmt = con.prepare(sql)?;
+        let columns: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        let num_cols = columns.len();
+        let mut state = start(columns)?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            f(&mut state, map_row(0, num_cols, row)?)?;
+        }
+        Ok(state)
+    }
+
+    /// Run a query, the first `key_cols` columns must be the rowid and are
+    /// returned separately from the rest of the row.
+    fn query_rows(&self, sql: &str, key_cols: usize) -> Result<(Vec<String>, KeyedRows), AppError> {
+        let con = self.connection()?;
+        let mut stmt = con.prepare(sql)?;
+        let num_cols = stmt.column_names().len();
+        let rows: Vec<_> = stmt
+            .query_map([], |row| {
+                let key = if key_cols > 0 { row.get(0)? } else { 0 };
+                Ok((key, map_row(key_cols, num_cols, row)?))
+            })?
+            .collect::<Result<_, _>>()?;
+        let cols = stmt
+            .column_names()
+            .iter()
+            .skip(key_cols)
+            .map(|s| s.to_string())
+            .collect();
+        Ok((cols, rows))
+    }
+
+    /// Names of all columns of a table or view.
+    pub fn table_columns(&self, table: &Table) -> Result<Vec<String>, AppError> {
+        let con = self.connection()?;
+        let stmt = con.prepare(&format!("SELECT * FROM {} LIMIT 0;", table.source()))?;
+        Ok(stmt.column_names().iter().map(|s| s.to_string()).collect())
+    }
+
+    /// Declared type of each column of a table or view, empty if none.
+    pub fn column_types(&self, table: &Table) -> Result<HashMap<String, String>, AppError> {
+        let con = self.connection()?;
+        let mut stmt = con.prepare("SELECT name, type FROM pragma_table_info(?1);")?;
+        let types = stmt
+            .query_map([&table.name], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        Ok(types)
+    }
+
+    pub fn prepare_total_rows(&self, table: &Table, filter: &Filter) -> Result<usize, AppError> {
+        let total = self
+            .connection()?
+            .query_row(&count_sql(table, filter), [], |r| r.get(0))?;
+        Ok(total)
+    }
+
+    /// Count the rows of a table on a separate connection and thread, the
+    /// result is sent on the returned channel.
+    pub fn count_rows_in_background(
+        &self,
+        table: &Table,
+        filter: &Filter,
+    ) -> Result<Receiver<Result<usize, AppError>>, AppError> {
+        let path = match &self.current_db {
+            Some(db) => db.path.clone(),
+            None => return Err(AppError::NoDatabase),
+        };
+        let sql = count_sql(table, filter);
+        let read_only = self.read_only;
+        let settings = self.settings.clone();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let res = open(&path, read_only, &settings)
+                .and_then(|con| con.query_row(&sql, [], |r| r.get(0)))
+                .map_err(AppError::from);
+            // the receiver is gone if the user moved on, nothing to do then
+            let _ = tx.send(res);
+        });
+        Ok(rx)
+    }
+
+    /// Count the rows of several tables one after the other on a separate
+    /// connection and thread. Each count is sent on the returned channel as
+    /// soon as it is done, along with its [`count_sql`].
+    pub fn count_tables_in_background(&self, tables: &[&Table]) -> Result<TableCounts, AppError> {
+        let db = self.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        let path = db.path.clone();
+        let sqls: Vec<String> = tables
+            .iter()
+            .map(|t| count_sql(t, &Filter::default()))
+            .collect();
+        let read_only = self.read_only;
+        let settings = self.settings.clone();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let con = match open(&path, read_only, &settings) {
+                Ok(con) => con,
+                Err(e) => {
+                    if let Some(sql) = sqls.into_iter().next() {
+                        let _ = tx.send((sql, Err(e.into())));
+                    }
+                    return;
+                }
+            };
+            for sql in sqls {
+                let res = con
+                    .query_row(&sql, [], |r| r.get(0))
+                    .map_err(AppError::from);
+                // stop once the user moved on to another database
+                if tx.send((sql, res)).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    /// Cheap estimate of the number of rows in a table.
+    ///
+    /// Uses the statistics gathered by `ANALYZE` when present, otherwise the
+    /// largest rowid. Returns None if neither is available.
+    pub fn estimate_rows(&self, table: &Table) -> Result<Option<usize>, AppError> {
+        if table.is_query {
+            return Ok(None);
+        }
+        let con = self.connection()?;
+        let has_stats: bool = con.query_row(
+            "SELECT count(*) > 0 FROM sqlite_master WHERE name = 'sqlite_stat1';",
+            [],
+            |r| r.get(0),
+        )?;
+        if has_stats {
+            // the row of the table itself if there is one, partial indexes
+            // only count the rows they cover
+            let mut stmt = con.prepare(
+                "SELECT idx IS NULL, stat FROM sqlite_stat1 WHERE tbl = ?1
+                 AND (idx IS NULL OR idx NOT IN
+                     (SELECT name FROM pragma_index_list(?1) WHERE partial));",
+            )?;
+            let stats: Vec<(bool, String)> = stmt
+                .query_map([&table.name], |r| Ok((r.get(0)?, r.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+            let rows = |stat: &str| stat.split(' ').next().and_then(|n| n.parse().ok());
+            let rows = match stats.iter().find(|(whole, _)| *whole) {
+                Some((_, stat)) => rows(stat),
+                None => stats.iter().filter_map(|(_, stat)| rows(stat)).max(),
+            };
+            if rows.is_some() {
+                return Ok(rows);
+            }
+        }
+        if table.has_rowid {
+            let sql = format!("SELECT max(rowid) FROM {};", quote_ident(&table.name));
+            let max: Option<i64> = con.query_row(&sql, [], |r| r.get(0))?;
+            return Ok(Some(max.unwrap_or(0).max(0) as usize));
+        }
+        Ok(None)
+    }
+
+    /// Run a single statement. Queries become the current query and are
+    /// browsed from there, anything else is executed right away.
+    pub fn run_query(&mut self, sql: &str) -> Result<QueryOutcome, AppError> {
+        let con = self.connection()?;
+        let mut batch = Batch::new(&con, sql);
+        let Some(mut stmt) = batch.next()? else {
+            return Ok(QueryOutcome::Changed(0));
+        };
+        // the rest fails to prepare if it uses what the first one creates
+        if !matches!(batch.next(), Ok(None)) {
+            return Err(AppError::Query("run one statement at a time".to_string()));
+        }
+        if stmt.column_count() == 0 {
+            return Ok(QueryOutcome::Changed(stmt.execute([])?));
+        }
+        // only queries can be wrapped in the SELECTs paging them
+        let keyword = first_keyword(sql).to_uppercase();
+        if stmt.readonly() && matches!(keyword.as_str(), "SELECT" | "WITH" | "VALUES") {
+            if let Some(db) = &mut self.current_db {
+                db.query = Some(Table::query(sql));
+            }
+            return Ok(QueryOutcome::Rows);
+        }
+        let columns: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        let num_cols = columns.len();
+        let rows = stmt
+            .query_map([], |row| map_row(0, num_cols, row))?
+            .collect::<Result<_, _>>()?;
+        Ok(QueryOutcome::Returned(columns, rows))
+    }
+
+    /// Run a maintenance task on a separate connection and thread, the
+    /// report is sent on the returned channel.
+    pub fn start_maintenance(
+        &self,
+        task: Maintenance,
+    ) -> Result<Receiver<Result<MaintenanceReport, AppError>>, AppError> {
+        let path = match &self.current_db {
+            Some(db) => db.path.clone(),
+            None => return Err(AppError::NoDatabase),
+        };
+        let read_only = self.read_only;
+        let settings = self.settings.clone();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let res = open(&path, read_only, &settings)
+                .map_err(AppError::from)
+                .and_then(|con| run_maintenance(&con, &path, &task));
+            // the receiver is gone if the user moved on, nothing to do then
+            let _ = tx.send(res);
+        });
+        Ok(rx)
+    }
+
+    /// Compare the current database with another on a separate thread,
+    /// the differences are sent on the returned channel.
+    pub fn start_diff(&self, other: &str) -> Result<Receiver<Result<DbDiff, AppError>>, AppError> {
+        let path = match &self.current_db {
+            Some(db) => db.path.clone(),
+            None => return Err(AppError::NoDatabase),
+        };
+        let other = other.to_string();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            // the receiver is gone if the user moved on, nothing to do then
+            let _ = tx.send(diff::diff(&path, &other, None));
+        });
+        Ok(rx)
+    }
+
+    /// Current value of every PRAGMA in [`PRAGMAS`], in order.
+    pub fn pragma_values(&self) -> Result<Vec<String>, AppError> {
+        let con = self.connection()?;
+        let values = PRAGMAS
+            .iter()
+            .map(|p| pragma_value(&con, p.name))
+            .collect::<Result<_, _>>()?;
+        Ok(values)
+    }
+
+    /// Change a PRAGMA. Connection PRAGMAs are remembered and applied to
+    /// every connection opened from now on, database ones are written to
+    /// the file. Returns the value read back from a new connection.
+    pub fn set_pragma(&mut self, name: &str, value: &str) -> Result<String, AppError> {
+        let info = PRAGMAS
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| AppError::Pragma(format!("{name} is unknown")))?;
+        let valid = value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if value.is_empty() || !valid {
+            return Err(AppError::Pragma(format!(
+                "{name}: {value:?} is not a valid value"
+            )));
+        }
+        match info.scope {
+            PragmaScope::ReadOnly => Err(AppError::Pragma(format!("{name} can only be read"))),
+            PragmaScope::Database if self.read_only => Err(AppError::Pragma(format!(
+                "{name}: the database is read-only"
+            ))),
+            PragmaScope::Database => {
+                self.connection()?
+                    .execute_batch(&format!("PRAGMA {name} = {value};"))?;
+                let now = pragma_value(&self.connection()?, name)?;
+                // other modes only last as long as the connection setting them
+                if name == "journal_mode" && !now.eq_ignore_ascii_case(value) {
+                    return Err(AppError::Pragma(format!(
+                        "journal_mode: {value} only lasts for one connection, the file stays in {now} mode"
+                    )));
+                }
+                Ok(now)
+            }
+            PragmaScope::Connection => {
+                // try it before keeping it, a bad value would break every connection
+                self.connection()?
+                    .execute_batch(&format!("PRAGMA {name} = {value};"))?;
+                self.settings.retain(|(n, _)| n != name);
+                self.settings.push((name.to_string(), value.to_string()));
+                Ok(pragma_value(&self.connection()?, name)?)
+            }
+        }
+    }
+
+    /// Change the schema of the current database and read its tables and
+    /// views again. Returns notes on anything else that had to change.
+    pub fn change_schema(&mut self, change: &SchemaChange) -> Result<Vec<String>, AppError> {
+        let notes = schema::apply(&self.connection()?, change)?;
+        let path = self.current_db.as_ref().map(|db| db.path.clone());
+        if let Some(path) = path {
+            self.load_db(&path)?;
+        }
+        Ok(notes)
+    }
+
+    /// Set a single value of a row identified by its rowid.
+    pub fn update_cell(
+        &self,
+        table: &Table,
+        column: &str,
+        rowid: i64,
+        value: &Value,
+    ) -> Result<usize, AppError> {
         let sql = format!(
-            "SELECT * FROM {} LIMIT {} OFFSET {};",
-            table.name, limit, offset
+            "UPDATE {} SET {} = ?1 WHERE rowid = ?2;",
+            quote_ident(&table.name),
+            quote_ident(column)
         );
-        if let Some(db) = &self.current_db {
-            let con = Connection::open(&db.path)?;
-            let mut stmt = con.prepare(&sql)?;
-            let num_cols = stmt.column_names().len();
-            let rows: Vec<_> = stmt
-                .query_map([], |row| map_row(num_cols, row))?
+        Ok(self
+            .connection()?
+            .execute(&sql, rusqlite::params![value, rowid])?)
+    }
+
+    /// Open a new connection to the current database.
+    fn connection(&self) -> Result<Connection, AppError> {
+        match &self.current_db {
+            Some(db) => Ok(open(&db.path, self.read_only, &self.settings)?),
+            None => Err(AppError::NoDatabase),
+        }
+    }
+}
+
+/// Open a connection to a database and apply the connection PRAGMAs set.
+/// Read-only connections fail on any write and never create the file.
+fn open(
+    path: &str,
+    read_only: bool,
+    settings: &[(String, String)],
+) -> Result<Connection, rusqlite::Error> {
+    let con = match read_only {
+        true => Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?,
+        false => Connection::open(path)?,
+    };
+    for (name, value) in settings {
+        con.execute_batch(&format!("PRAGMA {name} = {value};"))?;
+    }
+    Ok(con)
+}
+
+fn run_maintenance(
+    con: &Connection,
+    path: &str,
+    task: &Maintenance,
+) -> Result<MaintenanceReport, AppError> {
+    let sql = match task {
+        Maintenance::IntegrityCheck => "PRAGMA integrity_check;",
+        Maintenance::QuickCheck => "PRAGMA quick_check;",
+        Maintenance::ForeignKeyCheck => {
+            let mut stmt = con.prepare("PRAGMA foreign_key_check;")?;
+            let lines = stmt
+                .query_map([], |row| {
+                    let table: String = row.get(0)?;
+                    let rowid: Option<i64> = row.get(1)?;
+                    let parent: String = row.get(2)?;
+                    let rowid = rowid.map_or("row".to_string(), |id| format!("rowid {id}"));
+                    Ok(format!("{table} {rowid} has no matching row in {parent}"))
+                })?
                 .collect::<Result<_, _>>()?;
-            let cols = stmt.column_names().iter().map(|s| s.to_string()).collect();
-            return Ok((cols, rows));
+            return Ok(MaintenanceReport { lines, sizes: None });
         }
-        Ok((Vec::new(), Vec::new()))
+        Maintenance::Vacuum => "VACUUM;",
+        Maintenance::VacuumInto(_) => "VACUUM INTO ?1;",
+        Maintenance::Analyze => "ANALYZE;",
+        Maintenance::Reindex => "REINDEX;",
+        Maintenance::Optimize => "PRAGMA optimize;",
+    };
+    if task.is_check() {
+        let mut stmt = con.prepare(sql)?;
+        let lines: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        // a healthy database reports a single "ok"
+        let lines = match lines.as_slice() {
+            [ok] if ok == "ok" => Vec::new(),
+            _ => lines,
+        };
+        return Ok(MaintenanceReport { lines, sizes: None });
     }
+    let before = fs::metadata(path)?.len();
+    let after = match task {
+        Maintenance::VacuumInto(target) => {
+            con.execute(sql, [target])?;
+            fs::metadata(target)?.len()
+        }
+        _ => {
+            con.execute_batch(sql)?;
+            fs::metadata(path)?.len()
+        }
+    };
+    Ok(MaintenanceReport {
+        lines: Vec::new(),
+        sizes: Some((before, after)),
+    })
+}
+
+/// The query selecting a page of rows, see [`App::select`]. Tables with a
+/// rowid select it as the first column and are paged by key ranges unless
+/// sorted by a column.
+pub fn page_sql(
+    table: &Table,
+    columns: &[String],
+    filter: &Filter,
+    limit: usize,
+    seek: Seek,
+) -> String {
+    let name = table.source();
+    let cols = match columns.is_empty() {
+        true => "*".to_string(),
+        false => columns
+            .iter()
+            .map(|c| quote_ident(c))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    if !filter.pages_by_key(table) {
+        let offset = match seek {
+            Seek::Offset(offset) => offset,
+            _ => 0,
+        };
+        let key = if table.has_rowid { "rowid, " } else { "" };
+        let order = match filter.order_sql() {
+            // the rowid keeps equal values in a stable order across pages
+            Some(order) if table.has_rowid => format!(" ORDER BY {order}, rowid"),
+            Some(order) => format!(" ORDER BY {order}"),
+            None => String::new(),
+        };
+        let filtered = filter.where_sql(&[]);
+        return format!(
+            "SELECT {key}{cols} FROM {name}{filtered}{order} LIMIT {limit} OFFSET {offset};"
+        );
+    }
+    match seek {
+        Seek::Offset(offset) => {
+            let first = format!(
+                "rowid >= (SELECT rowid FROM {name}{} ORDER BY rowid LIMIT 1 OFFSET {offset})",
+                filter.where_sql(&[])
+            );
+            format!(
+                "SELECT rowid, {cols} FROM {name}{} ORDER BY rowid LIMIT {limit};",
+                filter.where_sql(&[first])
+            )
+        }
+        Seek::After(key) => format!(
+            "SELECT rowid, {cols} FROM {name}{} ORDER BY rowid LIMIT {limit};",
+            filter.where_sql(&[format!("rowid > {key}")])
+        ),
+        Seek::Before(key) => format!(
+            "SELECT rowid, {cols} FROM {name}{} ORDER BY rowid DESC LIMIT {limit};",
+            filter.where_sql(&[format!("rowid < {key}")])
+        ),
+        Seek::Last => format!(
+            "SELECT rowid, {cols} FROM {name}{} ORDER BY rowid DESC LIMIT {limit};",
+            filter.where_sql(&[])
+        ),
+    }
+}
+
+/// The query counting the rows of a table that match a filter, also used
+/// as the key rows counts are cached under.
+pub fn count_sql(table: &Table, filter: &Filter) -> String {
+    format!(
+        "SELECT COUNT(*) FROM {}{};",
+        table.source(),
+        filter.where_sql(&[])
+    )
+}
+
+/// The query a filter and sort ask for, without paging. Its plan shows
+/// whether an index would help.
+pub fn filter_sql(table: &Table, filter: &Filter) -> String {
+    let order = match filter.order_sql() {
+        Some(order) => format!(" ORDER BY {order}"),
+        None => String::new(),
+    };
+    format!(
+        "SELECT * FROM {}{}{order};",
+        table.source(),
+        filter.where_sql(&[])
+    )
+}
+
+/// Quote an identifier so it can be used in generated sql.
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
 
-    pub fn prepare_total_rows(&self, table: &Table) -> Result<usize, rusqlite::Error> {
-        let count_sql = format!("SELECT COUNT(*) FROM {};", table.name);
+/// Value of a PRAGMA as the Pragmas tab shows it.
+fn pragma_value(con: &Connection, name: &str) -> Result<String, rusqlite::Error> {
+    let value = con.pragma_query_value(None, name, |r| Ok(Value::from(r.get_ref(0)?)))?;
+    Ok(value.display().into_owned())
+}
 
-        if let Some(db) = &self.current_db {
-            let total = Connection::open(&db.path)?.query_row(&count_sql, [], |r| r.get(0))?;
-            Ok(total)
+/// First word of a statement, after any comments.
+fn first_keyword(sql: &str) -> &str {
+    let mut rest = sql.trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
         } else {
-            Ok(0)
+            break;
         }
+        rest = rest.trim_start();
     }
+    let end = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    &rest[..end]
 }
 
-fn map_row(num_of_columns: usize, row: &Row) -> Result<Vec<String>, rusqlite::Error> {
-    let mut data: Vec<String> = Vec::default();
-    for ind in 0..num_of_columns {
-        match row.get_ref(ind) {
-            Ok(column_ref) => match column_ref {
-                rusqlite::types::ValueRef::Null => {
-                    data.push("null".to_string());
-                }
-                rusqlite::types::ValueRef::Integer(v) => data.push(v.to_string()),
-                rusqlite::types::ValueRef::Real(v) => data.push(v.to_string()),
-                rusqlite::types::ValueRef::Text(s) => {
-                    data.push(if let Ok(s) = String::from_utf8(s.to_vec()) {
-                        s
-                    } else {
-                        "unreadable".to_string()
-                    });
-                }
-                rusqlite::types::ValueRef::Blob(_) => data.push("Blob".to_string()),
-            },
-            Err(e) => return Err(e),
-        }
+fn map_row(first: usize, num_of_columns: usize, row: &Row) -> Result<Vec<Value>, rusqlite::Error> {
+    let mut data: Vec<Value> = Vec::default();
+    for ind in first..num_of_columns {
+        data.push(Value::from(row.get_ref(ind)?));
     }
     Ok(data)
 }
 
+/// Bytes used by each table, summed over the b-trees of the table and its
+/// indexes.
+fn table_sizes(con: &Connection) -> Result<HashMap<String, u64>, rusqlite::Error> {
+    let mut stmt = con.prepare(
+        "SELECT m.tbl_name, sum(s.pgsize) FROM dbstat s
+         JOIN sqlite_master m ON m.name = s.name
+         WHERE s.aggregate = TRUE GROUP BY m.tbl_name;",
+    )?;
+    let sizes = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?
+        .collect();
+    sizes
+}
+
 /// Returns (Vec Tables, Vec Views)
 fn get_tables(con: &Connection) -> Result<(Vec<Table>, Vec<Table>), rusqlite::Error> {
     let mut stmt = con.prepare(SQL_TABLES)?;
@@ -112,22 +1176,47 @@ fn get_tables(con: &Connection) -> Result<(Vec<Table>, Vec<Table>), rusqlite::Er
         let table_name: String = row.get(3)?;
         Ok((type_id, name, sql, table_name))
     })?;
-    for (type_id, name, sql, _3) in rows.flatten() {
+    let mut indexes: Vec<(String, Index)> = Vec::default();
+    for (type_id, name, sql, table_name) in rows.flatten() {
+        if type_id == "index" {
+            indexes.push((table_name, Index { name, sql }));
+            continue;
+        }
+        let sql = sql.unwrap_or("".to_string());
         if type_id == "table" {
             tables.push(Table {
                 name,
-                sql: sql.unwrap_or("".to_string()),
+                has_rowid: table_has_rowid(&sql),
+                sql,
+                is_query: false,
+                indexes: Vec::new(),
             });
         } else if type_id == "view" {
             views.push(Table {
                 name,
-                sql: sql.unwrap_or("".to_string()),
+                sql,
+                has_rowid: false,
+                is_query: false,
+                indexes: Vec::new(),
             });
         }
     }
+    for (table_name, index) in indexes {
+        if let Some(table) = tables.iter_mut().find(|t| t.name == table_name) {
+            table.indexes.push(index);
+        }
+    }
     Ok((tables, views))
 }
 
+/// Virtual tables and `WITHOUT ROWID` tables can't be paged by rowid.
+pub fn table_has_rowid(sql: &str) -> bool {
+    // the words may be split by any whitespace, e.g. `WITHOUT\n  rowid`
+    let sql = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    let sql = sql.to_uppercase();
+    !sql.starts_with("CREATE VIRTUAL") && !sql.contains("WITHOUT ROWID")
+}
+
 /// Returns list of files in given directory matching [FILE_PATHS]
 pub fn load_files() -> io::Result<Vec<String>> {
     let mut files: Vec<String> = Vec::default();
@@ -143,3 +1232,149 @@ pub fn load_files() -> io::Result<Vec<String>> {
     }
     Ok(files)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// An app with a database made by the given sql in a file of its own.
+    fn app_with(name: &str, sql: &str) -> (App, PathBuf) {
+        let path = std::env::temp_dir().join(format!("jdbrowser-{}-{name}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        Connection::open(&path).unwrap().execute_batch(sql).unwrap();
+        let mut app = App::default();
+        app.load_db(path.to_str().unwrap()).unwrap();
+        (app, path)
+    }
+
+    fn table<'a>(app: &'a App, name: &str) -> &'a Table {
+        let db = app.current_db.as_ref().unwrap();
+        db.tables.iter().find(|t| t.name == name).unwrap()
+    }
+
+    fn rowid_table() -> Table {
+        Table {
+            name: "t".to_string(),
+            has_rowid: true,
+            ..Table::default()
+        }
+    }
+
+    fn filtered() -> Filter {
+        Filter {
+            condition: Some("a > 1".to_string()),
+            order: None,
+        }
+    }
+
+    #[test]
+    fn pages_by_rowid() {
+        let table = rowid_table();
+        let columns = vec!["a".to_string()];
+        let page = |filter: &Filter, seek| page_sql(&table, &columns, filter, 10, seek);
+        let no_filter = Filter::default();
+        assert_eq!(
+            page(&no_filter, Seek::Offset(20)),
+            "SELECT rowid, \"a\" FROM \"t\" WHERE rowid >= \
+             (SELECT rowid FROM \"t\" ORDER BY rowid LIMIT 1 OFFSET 20) ORDER BY rowid LIMIT 10;"
+        );
+        assert_eq!(
+            page(&filtered(), Seek::After(5)),
+            "SELECT rowid, \"a\" FROM \"t\" WHERE rowid > 5 AND (\na > 1\n) ORDER BY rowid LIMIT 10;"
+        );
+        assert_eq!(
+            page(&no_filter, Seek::Before(5)),
+            "SELECT rowid, \"a\" FROM \"t\" WHERE rowid < 5 ORDER BY rowid DESC LIMIT 10;"
+        );
+        assert_eq!(
+            page(&filtered(), Seek::Last),
+            "SELECT rowid, \"a\" FROM \"t\" WHERE (\na > 1\n) ORDER BY rowid DESC LIMIT 10;"
+        );
+    }
+
+    #[test]
+    fn pages_by_offset() {
+        let sorted = Filter {
+            order: Some(("a".to_string(), true)),
+            ..filtered()
+        };
+        assert_eq!(
+            page_sql(&rowid_table(), &[], &sorted, 10, Seek::Offset(20)),
+            "SELECT rowid, * FROM \"t\" WHERE (\na > 1\n) ORDER BY \"a\" DESC, rowid LIMIT 10 OFFSET 20;"
+        );
+        let view = Table {
+            name: "v".to_string(),
+            ..Table::default()
+        };
+        // other seeks don't apply without a rowid
+        assert_eq!(
+            page_sql(&view, &[], &Filter::default(), 10, Seek::After(5)),
+            "SELECT * FROM \"v\" LIMIT 10 OFFSET 0;"
+        );
+        assert_eq!(
+            count_sql(&Table::query("SELECT 1;"), &filtered()),
+            "SELECT COUNT(*) FROM (\nSELECT 1\n) WHERE (\na > 1\n);"
+        );
+    }
+
+    #[test]
+    fn pages_return_the_rows_asked_for() {
+        let con = Connection::open_in_memory().unwrap();
+        con.execute_batch(
+            "CREATE TABLE t (a);
+             INSERT INTO t (rowid, a) VALUES (1, 1), (2, 2), (4, 3), (7, 4), (9, 5);",
+        )
+        .unwrap();
+        let rows = |filter: &Filter, seek| -> Vec<i64> {
+            let sql = page_sql(&rowid_table(), &[], filter, 2, seek);
+            let mut stmt = con.prepare(&sql).unwrap();
+            let mut rows: Vec<i64> = stmt
+                .query_map([], |r| r.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            rows.sort();
+            rows
+        };
+        let all = Filter::default();
+        assert_eq!(rows(&all, Seek::Offset(2)), [4, 7]);
+        assert_eq!(rows(&all, Seek::After(4)), [7, 9]);
+        assert_eq!(rows(&all, Seek::Before(4)), [1, 2]);
+        assert_eq!(rows(&all, Seek::Last), [7, 9]);
+        assert_eq!(rows(&filtered(), Seek::Offset(1)), [4, 7]);
+        assert_eq!(rows(&filtered(), Seek::Before(7)), [2, 4]);
+    }
+
+    #[test]
+    fn finds_tables_without_rowid() {
+        assert!(table_has_rowid("CREATE TABLE t (a)"));
+        assert!(table_has_rowid("CREATE TABLE t (without_rowid)"));
+        assert!(!table_has_rowid(
+            "CREATE TABLE t (a PRIMARY KEY) WITHOUT ROWID"
+        ));
+        assert!(!table_has_rowid(
+            "create table t (a primary key)\nwithout\n\trowid"
+        ));
+        assert!(!table_has_rowid("CREATE VIRTUAL TABLE t USING fts5(a)"));
+    }
+
+    #[test]
+    fn estimates_rows_from_whole_table_stats() {
+        let (app, path) = app_with(
+            "estimate",
+            "CREATE TABLE t (a, b);
+             CREATE INDEX t_a ON t (a) WHERE a > 5;
+             CREATE INDEX t_b ON t (b);
+             CREATE TABLE u (a);
+             CREATE INDEX u_a ON u (a) WHERE a > 5;
+             ANALYZE;
+             DELETE FROM sqlite_stat1;
+             INSERT INTO sqlite_stat1 VALUES ('t', 't_a', '50 1'), ('t', 't_b', '10 2');
+             INSERT INTO sqlite_stat1 VALUES ('u', 'u_a', '50 1'), ('u', NULL, '12');",
+        );
+        assert_eq!(app.estimate_rows(table(&app, "t")).unwrap(), Some(10));
+        assert_eq!(app.estimate_rows(table(&app, "u")).unwrap(), Some(12));
+        fs::remove_file(path).unwrap();
+    }
+}
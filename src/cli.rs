@@ -0,0 +1,293 @@
+use crate::{
+    app::{self, App, Filter, QueryOutcome, Table},
+    diff::{self, Change, DbDiff},
+    error::AppError,
+    output::{Format, RowWriter},
+    value::Value,
+};
+use clap::Subcommand;
+use std::{
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+// Exit codes, so scripts can tell what went wrong.
+/// A comparison found differences, like `diff` exits
+const DIFFERENT: i32 = 1;
+/// The arguments are wrong, clap exits with the same code
+const USAGE: i32 = 2;
+/// The database is missing or can't be read
+const NO_DATABASE: i32 = 3;
+/// There is no table or view of the name given
+const NO_TABLE: i32 = 4;
+/// The sql failed
+const SQL_FAILED: i32 = 5;
+/// The output can't be written
+const OUTPUT_FAILED: i32 = 6;
+
+/// Commands run without the user interface, printing to stdout. All but
+/// `diff` work on the database given with `-f`.
+#[derive(Subcommand)]
+pub enum Command {
+    /// List the tables and views
+    Tables {
+        #[arg(long = "format", value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Print the statements creating a table or view and its indexes
+    Schema {
+        /// Name of the table or view
+        table: String,
+    },
+    /// Run a statement, printing the rows it returns
+    Query {
+        /// Statement to run
+        sql: String,
+        #[arg(long = "format", value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Print the rows of a table or view
+    Export {
+        /// Name of the table or view
+        table: String,
+        #[arg(long = "format", value_enum, default_value_t = Format::Csv)]
+        format: Format,
+        /// Only the rows matching a condition, as written after WHERE
+        #[arg(long = "where", value_name = "condition")]
+        condition: Option<String>,
+    },
+    /// Print the number of rows of a table or view
+    Count {
+        /// Name of the table or view
+        table: String,
+        /// Only count the rows matching a condition, as written after WHERE
+        #[arg(long = "where", value_name = "condition")]
+        condition: Option<String>,
+    },
+    /// Compare two databases, exits with 1 if they differ
+    Diff {
+        /// Database compared
+        from: PathBuf,
+        /// Database compared against, the patch turns the first into it
+        to: PathBuf,
+        /// Only compare this table
+        #[arg(long = "table", value_name = "name")]
+        table: Option<String>,
+        /// Print the sql turning the first database into the second
+        /// instead of the differences
+        #[arg(long = "patch")]
+        patch: bool,
+    },
+}
+
+/// Why a command failed, and the exit code telling scripts.
+struct Failure {
+    code: i32,
+    message: String,
+}
+
+impl Failure {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<AppError> for Failure {
+    fn from(e: AppError) -> Self {
+        match e {
+            AppError::Io(e) => Failure::from(e),
+            AppError::NoDatabase => Failure::new(NO_DATABASE, e.to_string()),
+            _ => Failure::new(SQL_FAILED, e.to_string()),
+        }
+    }
+}
+
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            // the reader stopped early, e.g. `| head`, nothing went wrong
+            ErrorKind::BrokenPipe => Failure::new(0, ""),
+            _ => Failure::new(OUTPUT_FAILED, format!("Can't write the output: {e}")),
+        }
+    }
+}
+
+/// Run a command on the database at `file`, errors are printed to stderr.
+/// Returns the exit code.
+pub fn run(command: Command, file: Option<&Path>, read_only: bool) -> i32 {
+    let res = match command {
+        Command::Diff {
+            from,
+            to,
+            table,
+            patch,
+        } => run_diff(&from, &to, table.as_deref(), patch),
+        // only queries may change the database
+        Command::Query { sql, format } => {
+            open(file, read_only).and_then(|app| run_query(app, &sql, format))
+        }
+        command => open(file, true).and_then(|app| run_on_table(&app, command)),
+    };
+    res.unwrap_or_else(|failure| {
+        if !failure.message.is_empty() {
+            eprintln!("{}", failure.message);
+        }
+        failure.code
+    })
+}
+
+/// Load the database, never creating it.
+fn open(file: Option<&Path>, read_only: bool) -> Result<App, Failure> {
+    let Some(file) = file else {
+        return Err(Failure::new(USAGE, "No database given, pass one with -f"));
+    };
+    if !file.is_file() {
+        let message = format!("{} is not a file", file.display());
+        return Err(Failure::new(NO_DATABASE, message));
+    }
+    let mut app = App {
+        read_only,
+        ..App::default()
+    };
+    app.load_db(&file.display().to_string())
+        .map_err(|e| Failure::new(NO_DATABASE, e.to_string()))?;
+    Ok(app)
+}
+
+fn find_table<'a>(app: &'a App, name: &str) -> Result<&'a Table, Failure> {
+    app.current_db
+        .iter()
+        .flat_map(|db| db.tables.iter().chain(&db.views))
+        .find(|t| t.name == name)
+        .ok_or_else(|| Failure::new(NO_TABLE, format!("No table or view named {name}")))
+}
+
+fn run_on_table(app: &App, command: Command) -> Result<i32, Failure> {
+    let mut out = io::stdout().lock();
+    match command {
+        Command::Tables { format } => {
+            let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+            let columns = vec!["name".to_string(), "type".to_string()];
+            let mut writer = RowWriter::new(out, format, columns)?;
+            let tables = db.tables.iter().map(|t| (t, "table"));
+            for (table, kind) in tables.chain(db.views.iter().map(|v| (v, "view"))) {
+                writer.row(&[text(&table.name), text(kind)])?;
+            }
+            writer.finish()?;
+        }
+        Command::Schema { table } => {
+            let table = find_table(app, &table)?;
+            writeln!(out, "{};", table.sql)?;
+            for sql in table.indexes.iter().filter_map(|i| i.sql.as_ref()) {
+                writeln!(out, "{sql};")?;
+            }
+        }
+        Command::Export {
+            table,
+            format,
+            condition,
+        } => {
+            let table = find_table(app, &table)?;
+            let filter = Filter {
+                condition,
+                order: None,
+            };
+            let sql = app::filter_sql(table, &filter);
+            write_rows(app, &sql, format, out)?;
+        }
+        Command::Count { table, condition } => {
+            let table = find_table(app, &table)?;
+            let filter = Filter {
+                condition,
+                order: None,
+            };
+            writeln!(out, "{}", app.prepare_total_rows(table, &filter)?)?;
+        }
+        Command::Query { .. } | Command::Diff { .. } => {}
+    }
+    Ok(0)
+}
+
+fn run_query(mut app: App, sql: &str, format: Format) -> Result<i32, Failure> {
+    match app.run_query(sql)? {
+        QueryOutcome::Changed(n) => writeln!(io::stdout(), "{n} rows changed")?,
+        QueryOutcome::Rows => write_rows(&app, sql, format, io::stdout().lock())?,
+        QueryOutcome::Returned(columns, rows) => {
+            let mut writer = RowWriter::new(io::stdout().lock(), format, columns)?;
+            for row in rows {
+                writer.row(&row)?;
+            }
+            writer.finish()?;
+        }
+    }
+    Ok(0)
+}
+
+/// Write the rows of a query as they are read.
+fn write_rows(app: &App, sql: &str, format: Format, out: impl Write) -> Result<(), Failure> {
+    let writer = app.stream_rows(
+        sql,
+        |columns| Ok(RowWriter::new(out, format, columns)?),
+        |writer, row| Ok(writer.row(&row)?),
+    )?;
+    writer.finish()?;
+    Ok(())
+}
+
+fn text(s: &str) -> Value {
+    Value::Text(s.as_bytes().to_vec())
+}
+
+fn run_diff(from: &Path, to: &Path, table: Option<&str>, patch: bool) -> Result<i32, Failure> {
+    for path in [from, to] {
+        if !path.is_file() {
+            let message = format!("{} is not a file", path.display());
+            return Err(Failure::new(NO_DATABASE, message));
+        }
+    }
+    let diff = diff::diff(
+        &from.display().to_string(),
+        &to.display().to_string(),
+        table,
+    )
+    // with both files there, only a missing table is left to complain about
+    .map_err(|e| match e {
+        AppError::Diff(_) => Failure::new(NO_TABLE, e.to_string()),
+        e => Failure::new(NO_DATABASE, e.to_string()),
+    })?;
+    let mut out = io::stdout().lock();
+    match patch {
+        true => {
+            diff::write_patch(&diff, &mut out)?;
+        }
+        false => print_diff(&diff, &mut out)?,
+    }
+    Ok(if diff.is_empty() { 0 } else { DIFFERENT })
+}
+
+/// Print what differs table by table, then the totals.
+fn print_diff(diff: &DbDiff, out: &mut impl Write) -> io::Result<()> {
+    for table in diff.tables.iter().filter(|t| t.change != Change::Same) {
+        let mark = table.change.mark();
+        writeln!(out, "{mark} table {}: {}", table.name, table.summary())?;
+        for item in &table.schema {
+            writeln!(out, "    {} {}", item.change.mark(), item.describe())?;
+        }
+        if let Some(key) = &table.key {
+            for row in &table.rows {
+                writeln!(out, "    {} {}", row.change.mark(), row.describe(key))?;
+            }
+            let more = table.added + table.removed + table.changed - table.rows.len();
+            if more > 0 && table.change == Change::Changed {
+                writeln!(out, "    ... {more} more rows")?;
+            }
+        }
+    }
+    for view in &diff.views {
+        writeln!(out, "{} {}", view.change.mark(), view.describe())?;
+    }
+    writeln!(out, "{}", diff.summary())
+}
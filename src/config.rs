@@ -0,0 +1,106 @@
+use crate::error::AppError;
+use serde::Deserialize;
+use std::{fs, io, path::PathBuf, str::FromStr};
+
+const APP_DIR: &str = "jdbrowser";
+const CONFIG_FILE: &str = "config.toml";
+
+/// Number of rows fetched per page.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "RawPageSize")]
+pub enum PageSize {
+    /// Fixed number of rows
+    Rows(usize),
+    /// As many rows as fit in the table area
+    Auto,
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        PageSize::Rows(50)
+    }
+}
+
+impl FromStr for PageSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(PageSize::Auto);
+        }
+        match s.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(PageSize::Rows(n)),
+            _ => Err(format!(
+                "invalid page size '{s}', expected a positive number or 'auto'"
+            )),
+        }
+    }
+}
+
+/// Page size as written in the config file, a number or "auto".
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawPageSize {
+    Rows(usize),
+    Named(String),
+}
+
+impl TryFrom<RawPageSize> for PageSize {
+    type Error = String;
+
+    fn try_from(raw: RawPageSize) -> Result<Self, Self::Error> {
+        match raw {
+            RawPageSize::Rows(n) => n.to_string().parse(),
+            RawPageSize::Named(s) => s.parse(),
+        }
+    }
+}
+
+/// User settings read from `config.toml` in the jdbrowser config directory.
+///
+/// Every setting is optional, command line arguments take precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub page_size: PageSize,
+    pub estimate_counts: bool,
+    pub continuous: bool,
+    /// Reload when the database changes on disk
+    pub watch: bool,
+    /// Milliseconds between checks for new rows of a followed table
+    pub follow_interval: Option<u64>,
+    /// Widest a column grows before its values are truncated
+    pub max_column_width: Option<usize>,
+}
+
+impl Config {
+    /// Load the config file, a missing file gives the default config.
+    pub fn load() -> Result<Config, AppError> {
+        let Some(path) = config_dir().map(|dir| dir.join(CONFIG_FILE)) else {
+            return Ok(Config::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|e| AppError::Config(format!("{}: {e}", path.display()))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Directory holding the config file.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR))
+}
+
+/// Directory holding state saved between sessions.
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR))
+}
+
+/// Databases are identified by their absolute path in saved state.
+pub fn db_key(db_path: &str) -> String {
+    fs::canonicalize(db_path)
+        .map(|p| p.display().to_string())
+        .unwrap_or(db_path.to_string())
+}
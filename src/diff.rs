@@ -0,0 +1,1059 @@
+use crate::{
+    app::{quote_ident, table_has_rowid},
+    error::AppError,
+    value::Value,
+};
+use rusqlite::{Connection, OpenFlags, Row};
+use std::{collections::BTreeSet, io::Write, path::Path};
+
+/// Differing rows of each kind kept to be shown, the counts and the patch
+/// cover every row.
+const ROWS_KEPT: usize = 500;
+
+/// Longest value shown in the description of a row.
+const VALUE_WIDTH: usize = 60;
+
+/// How an object of the first database compares to the second.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+    Same,
+}
+
+impl Change {
+    /// Mark shown in front of a changed item, like a unified diff.
+    pub fn mark(&self) -> char {
+        match self {
+            Change::Added => '+',
+            Change::Removed => '-',
+            Change::Changed => '~',
+            Change::Same => ' ',
+        }
+    }
+}
+
+/// A column, index, trigger or view that differs between the databases.
+#[derive(Debug, Clone)]
+pub struct SchemaDiff {
+    /// `column`, or the type of the `sqlite_master` entry
+    pub kind: String,
+    pub name: String,
+    pub change: Change,
+    /// Definition before and after, None where it is missing
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl SchemaDiff {
+    /// One line describing the difference, statements other than column
+    /// definitions are left out.
+    pub fn describe(&self) -> String {
+        let Self {
+            kind, name, change, ..
+        } = self;
+        if kind != "column" {
+            return format!("{kind} {name}");
+        }
+        let before = self.before.as_deref().unwrap_or_default();
+        let after = self.after.as_deref().unwrap_or_default();
+        match change {
+            Change::Changed => format!("{kind} {name} {before} -> {after}"),
+            Change::Removed => format!("{kind} {name} {before}"),
+            _ => format!("{kind} {name} {after}"),
+        }
+    }
+}
+
+/// A column with its value before and after.
+pub type ColumnChange = (String, Option<Value>, Option<Value>);
+
+/// A row added, removed or changed, matched by the key of its table.
+#[derive(Debug, Clone)]
+pub struct RowDiff {
+    pub change: Change,
+    /// Values of the key columns
+    pub key: Vec<Value>,
+    /// Columns with their value before and after. Changed rows only list
+    /// the columns that differ.
+    pub values: Vec<ColumnChange>,
+}
+
+impl RowDiff {
+    /// One line describing the row, e.g. `id=5: name 'a' -> 'b'`.
+    pub fn describe(&self, key: &[String]) -> String {
+        let keys = key
+            .iter()
+            .zip(&self.key)
+            .map(|(k, v)| format!("{k}={}", shown(v)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let values = self
+            .values
+            .iter()
+            .filter(|(column, ..)| !key.contains(column))
+            .map(|(column, before, after)| match (before, after) {
+                (Some(before), Some(after)) => {
+                    format!("{column} {} -> {}", shown(before), shown(after))
+                }
+                (Some(v), None) | (None, Some(v)) => format!("{column}={}", shown(v)),
+                (None, None) => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{keys}: {values}")
+    }
+}
+
+/// How a table compares, its schema and its rows.
+#[derive(Debug, Clone)]
+pub struct TableDiff {
+    pub name: String,
+    pub change: Change,
+    /// `CREATE TABLE` statements before and after, None where the table is
+    /// missing
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// Columns, indexes and triggers that differ
+    pub schema: Vec<SchemaDiff>,
+    /// Columns rows are matched by, None if rows can't be matched, e.g.
+    /// when the primary key changed
+    pub key: Option<Vec<String>>,
+    /// Rows added, all rows of an added table
+    pub added: usize,
+    /// Rows removed, all rows of a removed table
+    pub removed: usize,
+    pub changed: usize,
+    /// The first rows of each kind that differ, at most [`ROWS_KEPT`]
+    pub rows: Vec<RowDiff>,
+    columns_before: Vec<String>,
+    columns_after: Vec<String>,
+    /// Rows are matched by rowid, there is no primary key
+    by_rowid: bool,
+}
+
+impl TableDiff {
+    fn new(name: &str, before: Option<String>, after: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            change: Change::Same,
+            before,
+            after,
+            schema: Vec::new(),
+            key: None,
+            added: 0,
+            removed: 0,
+            changed: 0,
+            rows: Vec::new(),
+            columns_before: Vec::new(),
+            columns_after: Vec::new(),
+            by_rowid: false,
+        }
+    }
+
+    /// The `CREATE TABLE` statement changed, the patch recreates the table.
+    pub fn schema_changed(&self) -> bool {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => normalized(before) != normalized(after),
+            _ => false,
+        }
+    }
+
+    /// Short description of what differs, e.g. `schema, +3 -1 ~2 rows`.
+    pub fn summary(&self) -> String {
+        match self.change {
+            Change::Added => return format!("added, {} rows", self.added),
+            Change::Removed => return format!("removed, {} rows", self.removed),
+            _ => {}
+        }
+        let mut parts = Vec::new();
+        if self.schema_changed() || self.schema.iter().any(|s| s.kind == "column") {
+            parts.push("schema".to_string());
+        }
+        for kind in ["index", "trigger"] {
+            let n = self.schema.iter().filter(|s| s.kind == kind).count();
+            match n {
+                0 => {}
+                1 => parts.push(format!("1 {kind}")),
+                _ if kind == "index" => parts.push(format!("{n} indexes")),
+                _ => parts.push(format!("{n} {kind}s")),
+            }
+        }
+        match self.key {
+            None => parts.push("rows not compared".to_string()),
+            Some(_) if self.added + self.removed + self.changed > 0 => parts.push(format!(
+                "+{} -{} ~{} rows",
+                self.added, self.removed, self.changed
+            )),
+            Some(_) => {}
+        }
+        match parts.is_empty() {
+            true => "same".to_string(),
+            false => parts.join(", "),
+        }
+    }
+}
+
+/// Differences between two databases, see [`diff`].
+#[derive(Debug, Clone)]
+pub struct DbDiff {
+    /// Path of the first database, the patch turns it into the second
+    pub from: String,
+    pub to: String,
+    /// Every table of either database, unchanged ones too
+    pub tables: Vec<TableDiff>,
+    /// Views that differ
+    pub views: Vec<SchemaDiff>,
+}
+
+impl DbDiff {
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty() && self.tables.iter().all(|t| t.change == Change::Same)
+    }
+
+    /// Tables and views that differ, e.g. `2 tables changed, 1 added`.
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No differences".to_string();
+        }
+        let count = |change| self.tables.iter().filter(|t| t.change == change).count();
+        let mut parts = Vec::new();
+        for (change, label) in [
+            (Change::Changed, "changed"),
+            (Change::Added, "added"),
+            (Change::Removed, "removed"),
+            (Change::Same, "unchanged"),
+        ] {
+            match count(change) {
+                0 => {}
+                1 => parts.push(format!("1 table {label}")),
+                n => parts.push(format!("{n} tables {label}")),
+            }
+        }
+        match self.views.len() {
+            0 => {}
+            1 => parts.push("1 view differs".to_string()),
+            n => parts.push(format!("{n} views differ")),
+        }
+        parts.join(", ")
+    }
+}
+
+/// An entry of `sqlite_master`.
+struct Object {
+    kind: String,
+    name: String,
+    table: String,
+    sql: String,
+}
+
+/// A column as `PRAGMA table_info` describes it.
+struct Column {
+    name: String,
+    definition: String,
+    /// Position in the primary key, 0 if not part of it
+    pk: i64,
+}
+
+/// Schema the second database is attached as.
+const OTHER: &str = "other";
+
+/// Compare the schema and rows of the database at `from` with the one at
+/// `to`, or only the table named `only`. Rows are matched by primary key,
+/// or by rowid for tables without one. Rows of virtual tables are not
+/// compared.
+pub fn diff(from: &str, to: &str, only: Option<&str>) -> Result<DbDiff, AppError> {
+    let con = attach(from, to)?;
+    let before = objects(&con, "main")?;
+    let after = objects(&con, OTHER)?;
+    let wanted = |o: &&Object| only.is_none_or(|name| o.name == name);
+
+    let names: BTreeSet<&str> = before
+        .iter()
+        .chain(&after)
+        .filter(|o| o.kind == "table")
+        .filter(wanted)
+        .map(|o| o.name.as_str())
+        .collect();
+    if let (Some(name), true) = (only, names.is_empty()) {
+        return Err(AppError::Diff(format!(
+            "neither database has a table {name}"
+        )));
+    }
+    let mut tables = Vec::new();
+    for name in names {
+        tables.push(diff_table(&con, name, &before, &after)?);
+    }
+    let views_before: Vec<&Object> = before
+        .iter()
+        .filter(|o| o.kind == "view")
+        .filter(wanted)
+        .collect();
+    let views_after: Vec<&Object> = after
+        .iter()
+        .filter(|o| o.kind == "view")
+        .filter(wanted)
+        .collect();
+    Ok(DbDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        tables,
+        views: diff_objects(&views_before, &views_after),
+    })
+}
+
+/// Open the first database read-only with the second attached as
+/// [`OTHER`], also read-only.
+fn attach(from: &str, to: &str) -> Result<Connection, AppError> {
+    for path in [from, to] {
+        if !Path::new(path).is_file() {
+            return Err(AppError::Diff(format!("{path} is not a file")));
+        }
+    }
+    let con = Connection::open_with_flags(
+        from,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    con.execute(&format!("ATTACH DATABASE ?1 AS {OTHER};"), [to])?;
+    Ok(con)
+}
+
+/// Tables, indexes, views and triggers of a schema, without the internal
+/// ones of sqlite.
+fn objects(con: &Connection, schema: &str) -> Result<Vec<Object>, rusqlite::Error> {
+    let sql = format!(
+        r"SELECT type, name, tbl_name, sql FROM {schema}.sqlite_master
+          WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite\_%' ESCAPE '\' ORDER BY name;"
+    );
+    let mut stmt = con.prepare(&sql)?;
+    let objects = stmt
+        .query_map([], |row| {
+            Ok(Object {
+                kind: row.get(0)?,
+                name: row.get(1)?,
+                table: row.get(2)?,
+                sql: row.get(3)?,
+            })
+        })?
+        .collect();
+    objects
+}
+
+fn columns(con: &Connection, schema: &str, table: &str) -> Result<Vec<Column>, rusqlite::Error> {
+    let mut stmt = con.prepare(
+        "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1, ?2);",
+    )?;
+    let columns = stmt
+        .query_map([table, schema], |row| {
+            let mut definition: String = row.get(1)?;
+            if row.get(2)? {
+                definition.push_str(" NOT NULL");
+            }
+            if let Some(default) = row.get::<_, Option<String>>(3)? {
+                definition.push_str(&format!(" DEFAULT {default}"));
+            }
+            let pk: i64 = row.get(4)?;
+            if pk > 0 {
+                definition.push_str(" PRIMARY KEY");
+            }
+            Ok(Column {
+                name: row.get(0)?,
+                definition: definition.trim().to_string(),
+                pk,
+            })
+        })?
+        .collect();
+    columns
+}
+
+/// Names of the primary key columns in key order.
+fn primary_key(columns: &[Column]) -> Vec<String> {
+    let mut key: Vec<&Column> = columns.iter().filter(|c| c.pk > 0).collect();
+    key.sort_by_key(|c| c.pk);
+    key.into_iter().map(|c| c.name.clone()).collect()
+}
+
+fn diff_table(
+    con: &Connection,
+    name: &str,
+    before: &[Object],
+    after: &[Object],
+) -> Result<TableDiff, AppError> {
+    let find = |objects: &[Object]| {
+        objects
+            .iter()
+            .find(|o| o.kind == "table" && o.name == name)
+            .map(|o| o.sql.clone())
+    };
+    let mut table = TableDiff::new(name, find(before), find(after));
+    let count = |schema: &str| -> Result<usize, rusqlite::Error> {
+        let sql = format!("SELECT count(*) FROM {schema}.{};", quote_ident(name));
+        con.query_row(&sql, [], |row| row.get(0))
+    };
+    match (&table.before, &table.after) {
+        (Some(_), None) => {
+            table.change = Change::Removed;
+            table.removed = count("main")?;
+            return Ok(table);
+        }
+        (None, Some(_)) => {
+            table.change = Change::Added;
+            table.added = count(OTHER)?;
+            return Ok(table);
+        }
+        _ => {}
+    }
+
+    let columns_before = columns(con, "main", name)?;
+    let columns_after = columns(con, OTHER, name)?;
+    table.schema = diff_columns(&columns_before, &columns_after);
+    table.schema.extend(diff_objects(
+        &objects_of(before, name),
+        &objects_of(after, name),
+    ));
+    table.columns_before = columns_before.iter().map(|c| c.name.clone()).collect();
+    table.columns_after = columns_after.iter().map(|c| c.name.clone()).collect();
+
+    let (sql_before, sql_after) = (
+        find(before).unwrap_or_default(),
+        find(after).unwrap_or_default(),
+    );
+    let virtual_table = sql_before.to_uppercase().starts_with("CREATE VIRTUAL");
+    let (key_before, key_after) = (primary_key(&columns_before), primary_key(&columns_after));
+    table.key = match (key_before.is_empty(), key_before == key_after) {
+        _ if virtual_table => None,
+        (false, true) => Some(key_before),
+        (true, true) if table_has_rowid(&sql_before) && table_has_rowid(&sql_after) => {
+            table.by_rowid = true;
+            Some(vec!["rowid".to_string()])
+        }
+        _ => None,
+    };
+    diff_rows(con, &mut table)?;
+
+    let differs = table.schema_changed()
+        || !table.schema.is_empty()
+        || table.added + table.removed + table.changed > 0;
+    if differs {
+        table.change = Change::Changed;
+    }
+    Ok(table)
+}
+
+/// Indexes and triggers of a table.
+fn objects_of<'a>(objects: &'a [Object], table: &str) -> Vec<&'a Object> {
+    objects
+        .iter()
+        .filter(|o| o.kind != "table" && o.table == table)
+        .collect()
+}
+
+fn diff_columns(before: &[Column], after: &[Column]) -> Vec<SchemaDiff> {
+    let mut diffs = Vec::new();
+    for column in before {
+        let other = after.iter().find(|c| c.name == column.name);
+        let change = match other {
+            None => Change::Removed,
+            Some(other) if other.definition != column.definition => Change::Changed,
+            Some(_) => continue,
+        };
+        diffs.push(SchemaDiff {
+            kind: "column".to_string(),
+            name: column.name.clone(),
+            change,
+            before: Some(column.definition.clone()),
+            after: other.map(|c| c.definition.clone()),
+        });
+    }
+    for column in after {
+        if !before.iter().any(|c| c.name == column.name) {
+            diffs.push(SchemaDiff {
+                kind: "column".to_string(),
+                name: column.name.clone(),
+                change: Change::Added,
+                before: None,
+                after: Some(column.definition.clone()),
+            });
+        }
+    }
+    diffs
+}
+
+/// Compare `sqlite_master` entries by type and name, ignoring differences
+/// in whitespace.
+fn diff_objects(before: &[&Object], after: &[&Object]) -> Vec<SchemaDiff> {
+    let mut diffs = Vec::new();
+    for object in before {
+        let other = after
+            .iter()
+            .find(|o| o.kind == object.kind && o.name == object.name);
+        let change = match other {
+            None => Change::Removed,
+            Some(other) if normalized(&other.sql) != normalized(&object.sql) => Change::Changed,
+            Some(_) => continue,
+        };
+        diffs.push(SchemaDiff {
+            kind: object.kind.clone(),
+            name: object.name.clone(),
+            change,
+            before: Some(object.sql.clone()),
+            after: other.map(|o| o.sql.clone()),
+        });
+    }
+    for object in after {
+        if !before
+            .iter()
+            .any(|o| o.kind == object.kind && o.name == object.name)
+        {
+            diffs.push(SchemaDiff {
+                kind: object.kind.clone(),
+                name: object.name.clone(),
+                change: Change::Added,
+                before: None,
+                after: Some(object.sql.clone()),
+            });
+        }
+    }
+    diffs
+}
+
+fn normalized(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Queries selecting the rows of a table that differ, each starting with
+/// the key columns. Removed and added rows are followed by every column of
+/// the table before or after, changed rows by the value before and after
+/// of each column in both.
+struct RowQueries {
+    removed: String,
+    added: String,
+    changed: Option<String>,
+    /// Columns compared for changed rows
+    common: Vec<String>,
+}
+
+impl RowQueries {
+    /// None if the rows of the table can't be matched.
+    fn new(table: &TableDiff) -> Option<Self> {
+        let key = table.key.as_ref()?;
+        let ident = |column: &String| match table.by_rowid {
+            true => column.clone(),
+            false => quote_ident(column),
+        };
+        let keys = |alias: &str| {
+            key.iter()
+                .map(|k| format!("{alias}.{}", ident(k)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let values = |alias: &str, columns: &[String]| {
+            columns
+                .iter()
+                .map(|c| format!("{alias}.{}", quote_ident(c)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let on = key
+            .iter()
+            .map(|k| format!("a.{0} IS b.{0}", ident(k)))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let before = format!("main.{}", quote_ident(&table.name));
+        let after = format!("{OTHER}.{}", quote_ident(&table.name));
+
+        let removed = format!(
+            "SELECT {}, {} FROM {before} a WHERE NOT EXISTS (SELECT 1 FROM {after} b WHERE {on}) ORDER BY {};",
+            keys("a"),
+            values("a", &table.columns_before),
+            keys("a"),
+        );
+        let added = format!(
+            "SELECT {}, {} FROM {after} b WHERE NOT EXISTS (SELECT 1 FROM {before} a WHERE {on}) ORDER BY {};",
+            keys("b"),
+            values("b", &table.columns_after),
+            keys("b"),
+        );
+        let common: Vec<String> = table
+            .columns_before
+            .iter()
+            .filter(|c| table.columns_after.contains(c) && !key.contains(c))
+            .cloned()
+            .collect();
+        let changed = (!common.is_empty()).then(|| {
+            let pairs = common
+                .iter()
+                .map(|c| format!("a.{0}, b.{0}", quote_ident(c)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let differs = common
+                .iter()
+                .map(|c| format!("a.{0} IS NOT b.{0}", quote_ident(c)))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            format!(
+                "SELECT {}, {pairs} FROM {before} a JOIN {after} b ON {on} WHERE {differs} ORDER BY {};",
+                keys("a"),
+                keys("a"),
+            )
+        });
+        Some(Self {
+            removed,
+            added,
+            changed,
+            common,
+        })
+    }
+}
+
+/// Run a query calling `f` for each row, stopping at the first error.
+fn each_row<E: From<rusqlite::Error>>(
+    con: &Connection,
+    sql: &str,
+    mut f: impl FnMut(&Row) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut stmt = con.prepare(sql)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        f(row)?;
+    }
+    Ok(())
+}
+
+fn values(row: &Row, from: usize, to: usize) -> Result<Vec<Value>, rusqlite::Error> {
+    (from..to)
+        .map(|i| row.get_ref(i).map(Value::from))
+        .collect()
+}
+
+/// Count the rows that differ and keep the first of each kind.
+fn diff_rows(con: &Connection, table: &mut TableDiff) -> Result<(), rusqlite::Error> {
+    let Some(queries) = RowQueries::new(table) else {
+        return Ok(());
+    };
+    let k = table.key.as_ref().map_or(0, Vec::len);
+
+    let mut kept = 0;
+    let columns = table.columns_before.clone();
+    each_row(
+        con,
+        &queries.removed,
+        |row| -> Result<(), rusqlite::Error> {
+            table.removed += 1;
+            if kept < ROWS_KEPT {
+                kept += 1;
+                let values = values(row, k, k + columns.len())?;
+                table.rows.push(RowDiff {
+                    change: Change::Removed,
+                    key: values_of_key(row, k)?,
+                    values: columns
+                        .iter()
+                        .cloned()
+                        .zip(values)
+                        .map(|(c, v)| (c, Some(v), None))
+                        .collect(),
+                });
+            }
+            Ok(())
+        },
+    )?;
+
+    let mut kept = 0;
+    let columns = table.columns_after.clone();
+    each_row(con, &queries.added, |row| -> Result<(), rusqlite::Error> {
+        table.added += 1;
+        if kept < ROWS_KEPT {
+            kept += 1;
+            let values = values(row, k, k + columns.len())?;
+            table.rows.push(RowDiff {
+                change: Change::Added,
+                key: values_of_key(row, k)?,
+                values: columns
+                    .iter()
+                    .cloned()
+                    .zip(values)
+                    .map(|(c, v)| (c, None, Some(v)))
+                    .collect(),
+            });
+        }
+        Ok(())
+    })?;
+
+    let Some(changed) = &queries.changed else {
+        return Ok(());
+    };
+    let mut kept = 0;
+    each_row(con, changed, |row| {
+        table.changed += 1;
+        if kept < ROWS_KEPT {
+            kept += 1;
+            table.rows.push(RowDiff {
+                change: Change::Changed,
+                key: values_of_key(row, k)?,
+                values: changed_values(row, k, &queries.common)?,
+            });
+        }
+        Ok(())
+    })
+}
+
+fn values_of_key(row: &Row, k: usize) -> Result<Vec<Value>, rusqlite::Error> {
+    values(row, 0, k)
+}
+
+/// The columns of a changed row that differ, with their value before and
+/// after.
+fn changed_values(
+    row: &Row,
+    k: usize,
+    common: &[String],
+) -> Result<Vec<ColumnChange>, rusqlite::Error> {
+    let mut changed = Vec::new();
+    for (i, column) in common.iter().enumerate() {
+        let before = Value::from(row.get_ref(k + 2 * i)?);
+        let after = Value::from(row.get_ref(k + 2 * i + 1)?);
+        if before != after {
+            changed.push((column.clone(), Some(before), Some(after)));
+        }
+    }
+    Ok(changed)
+}
+
+/// A value as shown in a description, long values cut short.
+fn shown(value: &Value) -> String {
+    let text = value.sql_literal();
+    match text.char_indices().nth(VALUE_WIDTH) {
+        Some((at, _)) => format!("{}...", &text[..at]),
+        None => text,
+    }
+}
+
+/// Statements of a patch written out one per line.
+struct Patch<'a, W: Write> {
+    out: &'a mut W,
+    statements: usize,
+}
+
+impl<W: Write> Patch<'_, W> {
+    fn statement(&mut self, sql: &str) -> Result<(), AppError> {
+        writeln!(self.out, "{};", sql.trim_end().trim_end_matches(';'))?;
+        self.statements += 1;
+        Ok(())
+    }
+
+    fn insert(
+        &mut self,
+        table: &str,
+        columns: &[String],
+        values: &[Value],
+    ) -> Result<(), AppError> {
+        let columns = columns.join(", ");
+        let values = values
+            .iter()
+            .map(Value::sql_literal)
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.statement(&format!(
+            "INSERT INTO {}({columns}) VALUES({values})",
+            quote_ident(table)
+        ))
+    }
+}
+
+/// Write the statements turning the first database of a diff into the
+/// second, like `sqldiff` does, in a single transaction. Tables whose
+/// `CREATE TABLE` statement changed are dropped and created again with
+/// every row. Returns the number of statements written.
+pub fn write_patch(diff: &DbDiff, out: &mut impl Write) -> Result<usize, AppError> {
+    let con = attach(&diff.from, &diff.to)?;
+    let mut patch = Patch { out, statements: 0 };
+    patch.statement("BEGIN TRANSACTION")?;
+    // views may use the tables about to change, they are made last
+    for view in diff.views.iter().filter(|v| v.before.is_some()) {
+        patch.statement(&format!("DROP VIEW {}", quote_ident(&view.name)))?;
+    }
+    for table in &diff.tables {
+        patch_table(&con, table, &mut patch)?;
+    }
+    for view in &diff.views {
+        let Some(sql) = &view.after else {
+            continue;
+        };
+        patch.statement(sql)?;
+        // its INSTEAD OF triggers went with the view dropped
+        for (_, sql) in triggers_of(&con, OTHER, &view.name)? {
+            patch.statement(&sql)?;
+        }
+    }
+    patch.statement("COMMIT")?;
+    Ok(patch.statements)
+}
+
+fn patch_table<W: Write>(
+    con: &Connection,
+    table: &TableDiff,
+    patch: &mut Patch<W>,
+) -> Result<(), AppError> {
+    let name = quote_ident(&table.name);
+    match (&table.before, &table.after) {
+        _ if table.change == Change::Same => Ok(()),
+        (Some(_), None) => patch.statement(&format!("DROP TABLE {name}")),
+        (before, Some(after)) if before.is_none() || table.schema_changed() => {
+            if before.is_some() {
+                patch.statement(&format!("DROP TABLE {name}"))?;
+            }
+            patch.statement(after)?;
+            copy_table(con, table, after, patch)
+        }
+        _ => patch_rows(con, table, patch),
+    }
+}
+
+/// Insert every row of a table of the second database, then create its
+/// indexes and triggers.
+fn copy_table<W: Write>(
+    con: &Connection,
+    table: &TableDiff,
+    sql: &str,
+    patch: &mut Patch<W>,
+) -> Result<(), AppError> {
+    let mut columns: Vec<String> = columns(con, OTHER, &table.name)?
+        .iter()
+        .map(|c| quote_ident(&c.name))
+        .collect();
+    let virtual_table = sql.to_uppercase().starts_with("CREATE VIRTUAL");
+    if !virtual_table {
+        // keep the rowids of tables without an INTEGER PRIMARY KEY
+        if table_has_rowid(sql) {
+            columns.insert(0, "rowid".to_string());
+        }
+        let select = format!(
+            "SELECT {} FROM {OTHER}.{};",
+            columns.join(", "),
+            quote_ident(&table.name)
+        );
+        each_row(con, &select, |row| -> Result<(), AppError> {
+            patch.insert(&table.name, &columns, &values(row, 0, columns.len())?)
+        })?;
+    }
+    let mut stmt = con.prepare(&format!(
+        "SELECT sql FROM {OTHER}.sqlite_master
+         WHERE tbl_name = ?1 AND type IN ('index', 'trigger') AND sql IS NOT NULL;"
+    ))?;
+    let statements: Vec<String> = stmt
+        .query_map([&table.name], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    for sql in statements {
+        patch.statement(&sql)?;
+    }
+    Ok(())
+}
+
+/// Delete, update and insert the rows that differ in a table whose schema
+/// stayed the same, dropping and creating the indexes that differ around
+/// it. Every trigger of the table is dropped too, so none fires on the rows
+/// patched, and the ones of the second database are created after.
+fn patch_rows<W: Write>(
+    con: &Connection,
+    table: &TableDiff,
+    patch: &mut Patch<W>,
+) -> Result<(), AppError> {
+    let name = quote_ident(&table.name);
+    for (trigger, _) in triggers_of(con, "main", &table.name)? {
+        patch.statement(&format!("DROP TRIGGER {}", quote_ident(&trigger)))?;
+    }
+    let indexes = table.schema.iter().filter(|s| s.kind == "index");
+    for index in indexes.clone().filter(|o| o.before.is_some()) {
+        patch.statement(&format!("DROP INDEX {}", quote_ident(&index.name)))?;
+    }
+
+    if let Some(queries) = RowQueries::new(table) {
+        let key = table.key.clone().unwrap_or_default();
+        let keys: Vec<String> = match table.by_rowid {
+            true => key.clone(),
+            false => key.iter().map(|k| quote_ident(k)).collect(),
+        };
+        let matching = |row: &Row| -> Result<String, rusqlite::Error> {
+            Ok(keys
+                .iter()
+                .zip(values_of_key(row, keys.len())?)
+                .map(|(k, v)| format!("{k} IS {}", v.sql_literal()))
+                .collect::<Vec<_>>()
+                .join(" AND "))
+        };
+        each_row(con, &queries.removed, |row| -> Result<(), AppError> {
+            patch.statement(&format!("DELETE FROM {name} WHERE {}", matching(row)?))
+        })?;
+        if let Some(changed) = &queries.changed {
+            each_row(con, changed, |row| -> Result<(), AppError> {
+                let set = changed_values(row, keys.len(), &queries.common)?
+                    .into_iter()
+                    .map(|(column, _, after)| {
+                        let after = after.unwrap_or(Value::Null);
+                        format!("{} = {}", quote_ident(&column), after.sql_literal())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if set.is_empty() {
+                    // values sqlite sees as equal, e.g. 1 and 1.0
+                    return Ok(());
+                }
+                patch.statement(&format!("UPDATE {name} SET {set} WHERE {}", matching(row)?))
+            })?;
+        }
+        let mut columns: Vec<String> = table.columns_after.iter().map(|c| quote_ident(c)).collect();
+        let skip = match table.by_rowid {
+            true => {
+                columns.insert(0, "rowid".to_string());
+                0
+            }
+            // the key columns are among the columns too
+            false => keys.len(),
+        };
+        each_row(con, &queries.added, |row| -> Result<(), AppError> {
+            let values = values(row, skip, keys.len() + table.columns_after.len())?;
+            patch.insert(&table.name, &columns, &values)
+        })?;
+    }
+
+    for sql in indexes.filter_map(|o| o.after.as_ref()) {
+        patch.statement(sql)?;
+    }
+    for (_, sql) in triggers_of(con, OTHER, &table.name)? {
+        patch.statement(&sql)?;
+    }
+    Ok(())
+}
+
+/// Name and `CREATE TRIGGER` statement of every trigger of a table.
+fn triggers_of(
+    con: &Connection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<(String, String)>, rusqlite::Error> {
+    let mut stmt = con.prepare(&format!(
+        "SELECT name, sql FROM {schema}.sqlite_master
+         WHERE type = 'trigger' AND tbl_name = ?1 AND sql IS NOT NULL ORDER BY name;"
+    ))?;
+    let triggers = stmt
+        .query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect();
+    triggers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::PathBuf};
+
+    /// A database made by the given sql in a file of its own.
+    fn database(name: &str, sql: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("jdbrowser-{}-{name}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        Connection::open(&path).unwrap().execute_batch(sql).unwrap();
+        path
+    }
+
+    /// Every schema entry and every row of every table, in order.
+    fn contents(path: &Path) -> Vec<String> {
+        let con = Connection::open(path).unwrap();
+        let mut contents = Vec::new();
+        let objects = objects(&con, "main").unwrap();
+        for object in &objects {
+            contents.push(format!("{} {}: {}", object.kind, object.name, object.sql));
+        }
+        for table in objects.iter().filter(|o| o.kind == "table") {
+            let sql = format!("SELECT * FROM {} ORDER BY 1;", quote_ident(&table.name));
+            let mut stmt = con.prepare(&sql).unwrap();
+            let count = stmt.column_count();
+            let rows = stmt
+                .query_map([], |row| values(row, 0, count))
+                .unwrap()
+                .map(|row| format!("{}: {:?}", table.name, row.unwrap()));
+            contents.extend(rows);
+        }
+        contents
+    }
+
+    /// Apply the patch from the first database to the second to a copy of
+    /// the first and check the copy ends up like the second.
+    fn assert_patches(name: &str, from: &str, to: &str) {
+        let from = database(&format!("{name}-from"), from);
+        let to = database(&format!("{name}-to"), to);
+        let changes = diff(from.to_str().unwrap(), to.to_str().unwrap(), None).unwrap();
+        let mut patch = Vec::new();
+        write_patch(&changes, &mut patch).unwrap();
+        let patched = from.with_extension("patched.db");
+        fs::copy(&from, &patched).unwrap();
+        Connection::open(&patched)
+            .unwrap()
+            .execute_batch(&String::from_utf8(patch).unwrap())
+            .unwrap();
+        assert_eq!(contents(&patched), contents(&to));
+        let left = diff(patched.to_str().unwrap(), to.to_str().unwrap(), None).unwrap();
+        assert!(left.is_empty(), "{}", left.summary());
+        for path in [from, to, patched] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn patches_rows() {
+        assert_patches(
+            "rows",
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a, b);
+             CREATE TABLE n (a, b);
+             INSERT INTO t VALUES (1, 'a', 1), (2, 'b', 2), (3, 'c', 3);
+             INSERT INTO n VALUES ('x', 1), ('y', 2);",
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a, b);
+             CREATE TABLE n (a, b);
+             INSERT INTO t VALUES (1, 'a', 1), (2, 'B', NULL), (4, 'd', x'00ff');
+             INSERT INTO n VALUES ('x', 1), ('z', 3);",
+        );
+    }
+
+    #[test]
+    fn patches_schema() {
+        assert_patches(
+            "schema",
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a);
+             CREATE TABLE gone (a);
+             CREATE INDEX t_a ON t (a);
+             INSERT INTO t VALUES (1, 'a'), (2, 'b');
+             INSERT INTO gone VALUES (1);",
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a, b DEFAULT 0);
+             CREATE TABLE added (a TEXT NOT NULL);
+             CREATE INDEX t_b ON t (b);
+             INSERT INTO t VALUES (1, 'a', 1), (3, 'c', 0);
+             INSERT INTO added VALUES ('x');",
+        );
+    }
+
+    #[test]
+    fn patches_triggers_and_views() {
+        let log = "CREATE TABLE log (id INTEGER PRIMARY KEY, what);
+                   CREATE TABLE t (id INTEGER PRIMARY KEY, a);
+                   INSERT INTO t VALUES (1, 'a');";
+        assert_patches(
+            "triggers",
+            &format!(
+                "{log}
+                 CREATE TRIGGER t_log AFTER INSERT ON t BEGIN INSERT INTO log (what) VALUES ('insert'); END;
+                 CREATE TRIGGER t_gone AFTER DELETE ON t BEGIN INSERT INTO log (what) VALUES ('delete'); END;
+                 CREATE VIEW v AS SELECT a FROM t;
+                 CREATE VIEW w AS SELECT id FROM t;
+                 CREATE TRIGGER v_insert INSTEAD OF INSERT ON v BEGIN INSERT INTO t (a) VALUES (new.a); END;"
+            ),
+            &format!(
+                "{log}
+                 INSERT INTO t VALUES (2, 'b');
+                 CREATE TRIGGER t_log AFTER INSERT ON t BEGIN INSERT INTO log (what) VALUES ('added'); END;
+                 CREATE VIEW v AS SELECT id, a FROM t;
+                 CREATE VIEW x AS SELECT a FROM t;
+                 CREATE TRIGGER v_insert INSTEAD OF INSERT ON v BEGIN INSERT INTO t (id, a) VALUES (new.id, new.a); END;
+                 CREATE TRIGGER x_delete INSTEAD OF DELETE ON x BEGIN DELETE FROM t WHERE a = old.a; END;"
+            ),
+        );
+    }
+}
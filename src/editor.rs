@@ -0,0 +1,68 @@
+use crate::error::AppError;
+use std::{
+    collections::hash_map::RandomState,
+    env,
+    fs::{self, OpenOptions},
+    hash::{BuildHasher, Hasher},
+    io::{self, ErrorKind, Write},
+    path::PathBuf,
+    process::Command,
+};
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const DEFAULT_EDITOR: &str = "vi";
+/// Names tried for the temporary file before giving up
+const TEMP_ATTEMPTS: usize = 10;
+
+/// Let the user edit text in their `$VISUAL` or `$EDITOR`, through a
+/// temporary file with the given extension so the editor can pick a
+/// syntax. Returns the edited text.
+///
+/// The terminal must be handed over to the editor before calling this.
+pub fn edit(text: &str, extension: &str) -> Result<String, AppError> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or(DEFAULT_EDITOR.to_string());
+    // the variable may hold arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or(AppError::Editor("$EDITOR is empty".to_string()))?;
+
+    let path = create_temp_file(text, extension)?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .map_err(|e| AppError::Editor(format!("{program}: {e}")));
+    let edited = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path).map_err(AppError::from),
+        Ok(status) => Err(AppError::Editor(format!("{program} exited with {status}"))),
+        Err(e) => Err(e),
+    };
+    let _ = fs::remove_file(&path);
+    edited
+}
+
+/// Write the text to a new file in the temporary directory. The name is
+/// random and the file must not exist yet, so a file or link someone else
+/// placed there is never written through.
+fn create_temp_file(text: &str, extension: &str) -> io::Result<PathBuf> {
+    for _ in 0..TEMP_ATTEMPTS {
+        // every RandomState is seeded differently
+        let random = RandomState::new().build_hasher().finish();
+        let path = env::temp_dir().join(format!("jdbrowser-{random:016x}.{extension}"));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(text.as_bytes())?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(
+        ErrorKind::AlreadyExists,
+        "no free name for a temporary file",
+    ))
+}
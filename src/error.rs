@@ -0,0 +1,81 @@
+use std::{fmt, io};
+
+/// Errors that can occur while browsing a database.
+///
+/// None of these are fatal to the session, the ui reports them in a popup
+/// and carries on.
+#[derive(Debug)]
+pub enum AppError {
+    /// Error returned by sqlite, locked or malformed databases, bad sql etc.
+    Sqlite(rusqlite::Error),
+    /// Error reading or writing a file.
+    Io(io::Error),
+    /// Error accessing the system clipboard.
+    Clipboard(arboard::Error),
+    /// The config file could not be parsed.
+    Config(String),
+    /// The external editor could not be run or exited with an error.
+    Editor(String),
+    /// A PRAGMA can't be set to the value given.
+    Pragma(String),
+    /// A change to the schema can't be made.
+    Schema(String),
+    /// Two databases can't be compared.
+    Diff(String),
+    /// A statement can't be run as a query.
+    Query(String),
+    /// No database has been loaded yet.
+    NoDatabase,
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Sqlite(e) => write!(f, "Sqlite error: {e}"),
+            AppError::Io(e) => write!(f, "IO error: {e}"),
+            AppError::Clipboard(e) => write!(f, "Clipboard error: {e}"),
+            AppError::Config(e) => write!(f, "Invalid config file {e}"),
+            AppError::Editor(e) => write!(f, "Editor failed: {e}"),
+            AppError::Pragma(e) => write!(f, "Can't set PRAGMA {e}"),
+            AppError::Schema(e) => write!(f, "Can't change the schema, {e}"),
+            AppError::Diff(e) => write!(f, "Can't compare the databases, {e}"),
+            AppError::Query(e) => write!(f, "Can't run the query, {e}"),
+            AppError::NoDatabase => write!(f, "No database is open"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Sqlite(e) => Some(e),
+            AppError::Io(e) => Some(e),
+            AppError::Clipboard(e) => Some(e),
+            AppError::Config(_)
+            | AppError::Editor(_)
+            | AppError::Pragma(_)
+            | AppError::Schema(_)
+            | AppError::Diff(_)
+            | AppError::Query(_)
+            | AppError::NoDatabase => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        AppError::Sqlite(e)
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Io(e)
+    }
+}
+
+impl From<arboard::Error> for AppError {
+    fn from(e: arboard::Error) -> Self {
+        AppError::Clipboard(e)
+    }
+}
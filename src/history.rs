@@ -0,0 +1,103 @@
+use crate::{
+    config::{self, db_key},
+    error::AppError,
+};
+use rusqlite::{params, Connection};
+use std::{fs, path::PathBuf};
+
+const HISTORY_FILE: &str = "history.db";
+/// Most entries loaded at once, older ones stay in the file
+const MAX_ENTRIES: usize = 1000;
+
+const SQL_CREATE: &str = "
+    CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY,
+        sql TEXT NOT NULL,
+        db_path TEXT NOT NULL,
+        executed_at INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        row_count INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS history_db_path ON history (db_path, id);
+";
+
+/// A statement that was run.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub sql: String,
+    pub db_path: String,
+    /// Unix timestamp of when the statement was run
+    pub executed_at: i64,
+    pub duration_ms: u64,
+    /// Rows returned, or changed for statements that return none
+    pub row_count: Option<usize>,
+}
+
+/// Every statement run, kept in a small sqlite database in the data
+/// directory. The default history has no file and keeps nothing.
+#[derive(Debug, Default)]
+pub struct History {
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// Open the history file, creating it if needed.
+    pub fn open() -> Result<History, AppError> {
+        let Some(dir) = config::data_dir() else {
+            return Ok(History::default());
+        };
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(HISTORY_FILE);
+        Connection::open(&path)?.execute_batch(SQL_CREATE)?;
+        Ok(History { path: Some(path) })
+    }
+
+    pub fn record(&self, entry: &HistoryEntry) -> Result<(), AppError> {
+        let Some(con) = self.connection()? else {
+            return Ok(());
+        };
+        con.execute(
+            "INSERT INTO history (sql, db_path, executed_at, duration_ms, row_count)
+             VALUES (?1, ?2, ?3, ?4, ?5);",
+            params![
+                entry.sql,
+                db_key(&entry.db_path),
+                entry.executed_at,
+                entry.duration_ms,
+                entry.row_count
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Entries newest first, only those run against the given database or
+    /// all of them when none is given.
+    pub fn entries(&self, db_path: Option<&str>) -> Result<Vec<HistoryEntry>, AppError> {
+        let Some(con) = self.connection()? else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = con.prepare(
+            "SELECT sql, db_path, executed_at, duration_ms, row_count FROM history
+             WHERE ?1 IS NULL OR db_path = ?1 ORDER BY id DESC LIMIT ?2;",
+        )?;
+        let entries = stmt
+            .query_map(params![db_path.map(db_key), MAX_ENTRIES], |row| {
+                Ok(HistoryEntry {
+                    sql: row.get(0)?,
+                    db_path: row.get(1)?,
+                    executed_at: row.get(2)?,
+                    duration_ms: row.get(3)?,
+                    row_count: row.get(4)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(entries)
+    }
+
+    fn connection(&self) -> Result<Option<Connection>, AppError> {
+        match &self.path {
+            Some(path) => Ok(Some(Connection::open(path)?)),
+            None => Ok(None),
+        }
+    }
+}
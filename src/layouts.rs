@@ -0,0 +1,153 @@
+use crate::{
+    config::{self, db_key},
+    error::AppError,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+const LAYOUTS_FILE: &str = "layouts.toml";
+
+/// Which columns of a table are shown, and in what order.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnLayout {
+    /// Column order, columns not listed follow in table order
+    pub order: Vec<String>,
+    pub hidden: Vec<String>,
+    /// Number of leading shown columns that stay visible when scrolling
+    pub pinned: usize,
+}
+
+impl ColumnLayout {
+    /// All columns of the table in layout order, with whether each is shown.
+    /// If the layout hides every column, e.g. after the shown ones were
+    /// dropped, all are shown rather than none.
+    pub fn arrange(&self, columns: &[String]) -> Vec<(String, bool)> {
+        let mut arranged: Vec<String> = self
+            .order
+            .iter()
+            .filter(|c| columns.contains(c))
+            .cloned()
+            .collect();
+        for col in columns {
+            if !arranged.contains(col) {
+                arranged.push(col.clone());
+            }
+        }
+        let all_hidden = arranged.iter().all(|c| self.hidden.contains(c));
+        arranged
+            .into_iter()
+            .map(|c| {
+                let shown = all_hidden || !self.hidden.contains(&c);
+                (c, shown)
+            })
+            .collect()
+    }
+
+    /// Shown columns of the table in layout order.
+    pub fn shown(&self, columns: &[String]) -> Vec<String> {
+        self.arrange(columns)
+            .into_iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(c, _)| c)
+            .collect()
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == ColumnLayout::default()
+    }
+}
+
+/// Column layouts saved between sessions, keyed by database path and then
+/// table name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Layouts {
+    databases: BTreeMap<String, BTreeMap<String, ColumnLayout>>,
+}
+
+impl Layouts {
+    /// Load saved layouts, a missing file gives no layouts.
+    pub fn load() -> Result<Layouts, AppError> {
+        let Some(path) = layouts_path() else {
+            return Ok(Layouts::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|e| AppError::Config(format!("{}: {e}", path.display()))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Layouts::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn get(&self, db_path: &str, table: &str) -> ColumnLayout {
+        self.databases
+            .get(&db_key(db_path))
+            .and_then(|tables| tables.get(table))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Remember the layout of a table and write all layouts to disk.
+    pub fn save(
+        &mut self,
+        db_path: &str,
+        table: &str,
+        layout: ColumnLayout,
+    ) -> Result<(), AppError> {
+        let tables = self.databases.entry(db_key(db_path)).or_default();
+        if layout.is_default() {
+            tables.remove(table);
+        } else {
+            tables.insert(table.to_string(), layout);
+        }
+        self.databases.retain(|_, tables| !tables.is_empty());
+
+        let Some(path) = layouts_path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|e| AppError::Config(e.to_string()))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+fn layouts_path() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(LAYOUTS_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn shows_columns_in_layout_order() {
+        let layout = ColumnLayout {
+            order: names(&["c", "gone", "a"]),
+            hidden: names(&["b"]),
+            pinned: 0,
+        };
+        assert_eq!(
+            layout.shown(&names(&["a", "b", "c", "d"])),
+            names(&["c", "a", "d"])
+        );
+    }
+
+    #[test]
+    fn never_hides_every_column() {
+        let layout = ColumnLayout {
+            order: Vec::new(),
+            hidden: names(&["a", "b"]),
+            pinned: 0,
+        };
+        assert_eq!(layout.shown(&names(&["a", "b"])), names(&["a", "b"]));
+        assert_eq!(layout.shown(&names(&["a", "b", "c"])), names(&["c"]));
+    }
+}
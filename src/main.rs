@@ -1,10 +1,15 @@
 use app::App;
 use clap::Parser;
+use cli::Command;
+use config::{Config, PageSize};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use error::AppError;
+use history::History;
+use layouts::Layouts;
 use ratatui::{
     prelude::{Backend, CrosstermBackend},
     restore, Terminal,
@@ -12,23 +17,73 @@ use ratatui::{
 use std::{
     io::{self},
     path::PathBuf,
+    time::Duration,
 };
-use ui::Ui;
+use ui::{table_view::ScrollMode, Ui};
 
 pub mod app;
+pub mod cli;
+pub mod config;
+pub mod diff;
+pub mod editor;
+pub mod error;
+pub mod history;
+pub mod layouts;
+pub mod output;
+pub mod schema;
+pub mod snippets;
 pub mod ui;
+pub mod value;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct CliArgs {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the database file
-    #[arg(short = 'f', long = "file", value_name = "sqlite database")]
+    #[arg(
+        short = 'f',
+        long = "file",
+        value_name = "sqlite database",
+        global = true
+    )]
     file: Option<PathBuf>,
+
+    /// Show fast approximate row counts while exact counts run in the background
+    #[arg(long = "estimate-counts")]
+    estimate_counts: bool,
+
+    /// Scroll through rows continuously instead of by page
+    #[arg(long = "continuous")]
+    continuous: bool,
+
+    /// Rows per page, a number or "auto" to fit the screen
+    #[arg(long = "page-size", value_name = "rows|auto")]
+    page_size: Option<PageSize>,
+
+    /// Open databases read-only, editing and maintenance are disabled
+    #[arg(long = "read-only", global = true)]
+    read_only: bool,
+
+    /// Reload tables and rows when the database changes on disk
+    #[arg(long = "watch")]
+    watch: bool,
+
+    /// Milliseconds between checks for new rows of a followed table
+    #[arg(long = "follow-interval", value_name = "ms")]
+    follow_interval: Option<u64>,
 }
 
+/// How long to wait for input before updating background work
+const TICK_RATE: Duration = Duration::from_millis(250);
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     color_eyre::install()?;
     let cli = CliArgs::parse();
+    if let Some(command) = cli.command {
+        std::process::exit(cli::run(command, cli.file.as_deref(), cli.read_only));
+    }
 
     let mut app = App::default();
     let mut ui = Ui::new()?;
@@ -42,15 +97,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = CrosstermBackend::new(stderr);
     let mut terminal = Terminal::new(backend)?;
 
-    // Load file if given as argument
-    handle_cli(cli, &mut app, &mut ui)?;
+    let config = Config::load().unwrap_or_else(|e| {
+        ui.report_error(e);
+        Config::default()
+    });
 
-    let res = run_app(&mut terminal, &mut app, &mut ui);
+    ui.table_view.layouts = Layouts::load().unwrap_or_else(|e| {
+        ui.report_error(e);
+        Layouts::default()
+    });
 
-    if let Err(err) = res {
-        eprintln!("{err}")
+    ui.table_view.history = History::open().unwrap_or_else(|e| {
+        ui.report_error(e);
+        History::default()
+    });
+
+    // Load file if given as argument
+    if let Err(e) = handle_cli(cli, config, &mut app, &mut ui) {
+        ui.report_error(e);
     }
 
+    let res = run_app(&mut terminal, &mut app, &mut ui);
+
     // restore terminal
     disable_raw_mode()?;
     execute!(
@@ -60,16 +128,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     terminal.show_cursor()?;
 
+    // only terminal failures reach here, everything else is shown in the ui
+    if let Err(err) = res {
+        eprintln!("{err}")
+    }
+
     Ok(())
 }
 
-fn handle_cli(cli: CliArgs, app: &mut App, ui: &mut Ui) -> Result<(), Box<dyn std::error::Error>> {
-    Ok(if let Some(file_path) = cli.file.as_deref() {
+fn handle_cli(cli: CliArgs, config: Config, app: &mut App, ui: &mut Ui) -> Result<(), AppError> {
+    app.read_only = cli.read_only;
+    ui.table_view.estimate_counts = cli.estimate_counts || config.estimate_counts;
+    ui.table_view.watch = cli.watch || config.watch;
+    if let Some(ms) = cli.follow_interval.or(config.follow_interval) {
+        ui.table_view.follow_interval = Duration::from_millis(ms);
+    }
+    if cli.continuous || config.continuous {
+        ui.table_view.scroll_mode = ScrollMode::Continuous;
+    }
+    ui.table_view
+        .set_page_size(cli.page_size.unwrap_or(config.page_size));
+    if let Some(width) = config.max_column_width {
+        ui.table_view.columns.max_width = width;
+    }
+    if let Some(file_path) = cli.file.as_deref() {
         app.load_db(file_path.display().to_string().as_str())?;
-        if let Some(db) = &app.current_db {
-            ui.table_view.load_nav(db);
-        }
-    })
+        ui.table_view.load_nav(app)?;
+    }
+    Ok(())
 }
 
 fn run_app<B: Backend>(
@@ -79,20 +165,42 @@ fn run_app<B: Backend>(
 ) -> Result<(), Box<dyn std::error::Error>> {
     loop {
         terminal.draw(|f| ui.ui(f, app))?;
+        ui.tick(app);
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
         if let Event::Key(event) = event::read()? {
             if event.kind == event::KeyEventKind::Release {
                 // Skip events that are not KeyEventKind::Press
                 continue;
             }
-            ui.handle_input(&event, app)?;
-            if event.code == KeyCode::Esc {
+            ui.handle_input(&event, app);
+            if ui.should_quit() {
                 break;
             }
+            if let Some(request) = ui.take_edit_request() {
+                // hand the terminal to the editor while it runs
+                leave_terminal()?;
+                let edited = editor::edit(&request.text, request.extension);
+                enter_terminal()?;
+                terminal.clear()?;
+                ui.finish_edit(app, request, edited);
+            }
         }
     }
     Ok(())
 }
 
+fn leave_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)
+}
+
+fn enter_terminal() -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)
+}
+
 fn set_panic_hook() {
     let hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
@@ -0,0 +1,204 @@
+use crate::value::Value;
+use clap::ValueEnum;
+use serde_json::{Map, Number, Value as Json};
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthStr;
+
+/// How rows are printed by the command line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Columns aligned for reading
+    #[default]
+    Table,
+    /// Comma separated values with a header, `NULL` left empty
+    Csv,
+    /// An array with an object per row
+    Json,
+    /// A markdown table
+    Md,
+}
+
+/// Writes rows in a [`Format`] as they come. Aligned tables are held back
+/// until [`RowWriter::finish`] as their widths depend on every row.
+pub struct RowWriter<W: Write> {
+    out: W,
+    format: Format,
+    columns: Vec<String>,
+    held: Vec<Vec<String>>,
+    rows: usize,
+}
+
+impl<W: Write> RowWriter<W> {
+    /// Start the output, writing the header where the format has one.
+    pub fn new(mut out: W, format: Format, columns: Vec<String>) -> io::Result<Self> {
+        let columns = match format {
+            Format::Json => unique_keys(columns),
+            _ => columns,
+        };
+        match format {
+            Format::Table => {}
+            Format::Csv => {
+                let header: Vec<String> = columns.iter().map(|c| csv_field(c)).collect();
+                writeln!(out, "{}", header.join(","))?;
+            }
+            Format::Json => write!(out, "[")?,
+            Format::Md => {
+                let header: Vec<String> = columns.iter().map(|c| md_cell(c)).collect();
+                writeln!(out, "| {} |", header.join(" | "))?;
+                writeln!(out, "|{}", " --- |".repeat(columns.len()))?;
+            }
+        }
+        Ok(Self {
+            out,
+            format,
+            columns,
+            held: Vec::new(),
+            rows: 0,
+        })
+    }
+
+    pub fn row(&mut self, row: &[Value]) -> io::Result<()> {
+        match self.format {
+            Format::Table => self.held.push(row.iter().map(table_cell).collect()),
+            Format::Csv => {
+                let fields: Vec<String> = row.iter().map(|v| csv_field(&csv_text(v))).collect();
+                writeln!(self.out, "{}", fields.join(","))?;
+            }
+            Format::Json => {
+                let object: Map<String, Json> = self
+                    .columns
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(json_value))
+                    .collect();
+                let separator = if self.rows == 0 { "" } else { "," };
+                write!(self.out, "{separator}\n  {}", Json::Object(object))?;
+            }
+            Format::Md => {
+                let cells: Vec<String> = row.iter().map(|v| md_cell(&v.display())).collect();
+                writeln!(self.out, "| {} |", cells.join(" | "))?;
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// End the output. Returns the number of rows written.
+    pub fn finish(mut self) -> io::Result<usize> {
+        match self.format {
+            Format::Table => self.write_table()?,
+            Format::Json if self.rows == 0 => writeln!(self.out, "]")?,
+            Format::Json => writeln!(self.out, "\n]")?,
+            Format::Csv | Format::Md => {}
+        }
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+
+    fn write_table(&mut self) -> io::Result<()> {
+        let mut widths: Vec<usize> = self.columns.iter().map(|c| c.width()).collect();
+        for row in &self.held {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - cell.width())))
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        };
+        writeln!(self.out, "{}", line(&self.columns))?;
+        let rules: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        writeln!(self.out, "{}", rules.join("  "))?;
+        for row in &self.held {
+            writeln!(self.out, "{}", line(row))?;
+        }
+        Ok(())
+    }
+}
+
+/// Column names made unique to serve as the keys of a json object, a name
+/// seen before gets the first free suffix, e.g. `id`, `id:1`.
+fn unique_keys(columns: Vec<String>) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::with_capacity(columns.len());
+    for column in &columns {
+        let mut key = column.clone();
+        let mut n = 0;
+        while keys.contains(&key) || (n > 0 && columns.contains(&key)) {
+            n += 1;
+            key = format!("{column}:{n}");
+        }
+        keys.push(key);
+    }
+    keys
+}
+
+/// A value on a single line, as the table view shows it.
+fn table_cell(value: &Value) -> String {
+    value
+        .display()
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// A value as csv text, `NULL` is left empty and blobs are written in hex.
+fn csv_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Blob(bytes) => hex(bytes),
+        _ => value.display().into_owned(),
+    }
+}
+
+/// Quote a csv field when it holds a separator, quote or line break.
+fn csv_field(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// A value as json, blobs become hex strings.
+fn json_value(value: &Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::Integer(v) => Json::from(*v),
+        Value::Real(v) => Number::from_f64(*v).map_or(Json::Null, Json::Number),
+        Value::Text(_) => Json::from(value.display().into_owned()),
+        Value::Blob(bytes) => Json::from(hex(bytes)),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn json_keys_stay_unique() {
+        let mut out = Vec::new();
+        let mut writer =
+            RowWriter::new(&mut out, Format::Json, names(&["id", "id", "id:1", "id"])).unwrap();
+        writer.row(&[1, 2, 3, 4].map(Value::Integer)).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[\n  {\"id\":1,\"id:2\":2,\"id:1\":3,\"id:3\":4}\n]\n"
+        );
+    }
+}
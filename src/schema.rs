@@ -0,0 +1,612 @@
+use crate::{
+    app::{quote_ident, Filter, Table},
+    error::AppError,
+};
+use rusqlite::Connection;
+use sqlparser::{
+    dialect::SQLiteDialect,
+    keywords::Keyword,
+    tokenizer::{Location, Token, TokenWithSpan, Tokenizer},
+};
+
+/// A change to the schema of the database.
+#[derive(Debug, Clone)]
+pub enum SchemaChange {
+    /// Create a table from a full `CREATE TABLE` statement
+    CreateTable {
+        name: String,
+        sql: String,
+    },
+    RenameTable {
+        table: String,
+        to: String,
+    },
+    RenameColumn {
+        table: String,
+        column: String,
+        to: String,
+    },
+    /// Add a column given its definition, e.g. `age INTEGER NOT NULL DEFAULT 0`
+    AddColumn {
+        table: String,
+        definition: String,
+    },
+    DropColumn {
+        table: String,
+        column: String,
+    },
+    DropTable {
+        table: String,
+    },
+    DropView {
+        view: String,
+    },
+    /// Create an index from a full `CREATE INDEX` statement
+    CreateIndex {
+        table: String,
+        sql: String,
+    },
+    DropIndex {
+        table: String,
+        index: String,
+    },
+}
+
+impl SchemaChange {
+    /// The table to show once the change is made, None if it is gone.
+    pub fn table_after(&self) -> Option<&str> {
+        match self {
+            SchemaChange::CreateTable { name, .. } => Some(name),
+            SchemaChange::RenameTable { to, .. } => Some(to),
+            SchemaChange::RenameColumn { table, .. }
+            | SchemaChange::AddColumn { table, .. }
+            | SchemaChange::DropColumn { table, .. }
+            | SchemaChange::CreateIndex { table, .. }
+            | SchemaChange::DropIndex { table, .. } => Some(table),
+            SchemaChange::DropTable { .. } | SchemaChange::DropView { .. } => None,
+        }
+    }
+}
+
+/// Make a change to the schema. Returns notes on what else had to change,
+/// e.g. indexes dropped along with a column.
+pub fn apply(con: &Connection, change: &SchemaChange) -> Result<Vec<String>, AppError> {
+    let sql = match change {
+        SchemaChange::CreateTable { sql, .. } | SchemaChange::CreateIndex { sql, .. } => {
+            sql.clone()
+        }
+        SchemaChange::RenameTable { table, to } => format!(
+            "ALTER TABLE {} RENAME TO {};",
+            quote_ident(table),
+            quote_ident(to)
+        ),
+        SchemaChange::RenameColumn { table, column, to } => format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {};",
+            quote_ident(table),
+            quote_ident(column),
+            quote_ident(to)
+        ),
+        SchemaChange::AddColumn { table, definition } => {
+            format!(
+                "ALTER TABLE {} ADD COLUMN {definition};",
+                quote_ident(table)
+            )
+        }
+        SchemaChange::DropColumn { table, column } => {
+            let sql = format!(
+                "ALTER TABLE {} DROP COLUMN {};",
+                quote_ident(table),
+                quote_ident(column)
+            );
+            // keys, unique and indexed columns can't be dropped in place
+            return match con.execute_batch(&sql) {
+                Ok(()) => Ok(Vec::new()),
+                Err(e) if needs_rebuild(&e) => rebuild_without_column(con, table, column),
+                Err(e) => Err(e.into()),
+            };
+        }
+        SchemaChange::DropTable { table } => format!("DROP TABLE {};", quote_ident(table)),
+        SchemaChange::DropView { view } => format!("DROP VIEW {};", quote_ident(view)),
+        SchemaChange::DropIndex { index, .. } => format!("DROP INDEX {};", quote_ident(index)),
+    };
+    con.execute_batch(&sql)?;
+    Ok(Vec::new())
+}
+
+/// The `CREATE INDEX` statement of an index on the given columns, each
+/// with true for descending order. A condition makes a partial index.
+pub fn create_index_sql(
+    name: &str,
+    table: &str,
+    columns: &[(String, bool)],
+    unique: bool,
+    condition: Option<&str>,
+) -> String {
+    let columns = columns
+        .iter()
+        .map(|(column, descending)| {
+            let direction = if *descending { " DESC" } else { "" };
+            format!("{}{direction}", quote_ident(column))
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let unique = if unique { "UNIQUE " } else { "" };
+    let condition = match condition {
+        Some(condition) => format!(" WHERE {condition}"),
+        None => String::new(),
+    };
+    format!(
+        "CREATE {unique}INDEX {} ON {} ({columns}){condition};",
+        quote_ident(name),
+        quote_ident(table)
+    )
+}
+
+/// Name for a new index of a table on the given columns.
+pub fn index_name(table: &str, columns: &[&str]) -> String {
+    format!("idx_{table}_{}", columns.join("_"))
+}
+
+/// An index serving a filter and sort: the columns the condition uses in
+/// order of first use, then the sort column. None if the filter uses none
+/// of the columns, or the table already has the index.
+pub fn suggest_index(table: &Table, columns: &[String], filter: &Filter) -> Option<SchemaChange> {
+    let mut used: Vec<(String, bool)> = Vec::new();
+    if let Some(condition) = &filter.condition {
+        let tokens = Tokenizer::new(&SQLiteDialect {}, condition)
+            .tokenize()
+            .unwrap_or_default();
+        for token in tokens {
+            let Token::Word(word) = token else {
+                continue;
+            };
+            let column = columns.iter().find(|c| c.eq_ignore_ascii_case(&word.value));
+            if let Some(column) = column {
+                if !used.iter().any(|(c, _)| c == column) {
+                    used.push((column.clone(), false));
+                }
+            }
+        }
+    }
+    if let Some((column, descending)) = &filter.order {
+        if !used.iter().any(|(c, _)| c == column) {
+            used.push((column.clone(), *descending));
+        }
+    }
+    if used.is_empty() {
+        return None;
+    }
+    let names: Vec<&str> = used.iter().map(|(c, _)| c.as_str()).collect();
+    let name = index_name(&table.name, &names);
+    if table.indexes.iter().any(|i| i.name == name) {
+        return None;
+    }
+    Some(SchemaChange::CreateIndex {
+        table: table.name.clone(),
+        sql: create_index_sql(&name, &table.name, &used, false, None),
+    })
+}
+
+/// True if `ALTER TABLE DROP COLUMN` failed on something a rebuild of the
+/// table gets around: a key, a unique constraint, or an index, trigger or
+/// table constraint using the column. Views using it stay an error.
+fn needs_rebuild(e: &rusqlite::Error) -> bool {
+    let rusqlite::Error::SqliteFailure(_, Some(message)) = e else {
+        return false;
+    };
+    let in_place = [
+        "cannot drop PRIMARY KEY column",
+        "cannot drop UNIQUE column",
+    ];
+    let using = ["error in index ", "error in trigger ", "error in table "];
+    in_place.iter().any(|m| message.starts_with(m))
+        || (using.iter().any(|m| message.starts_with(m))
+            && message.contains(" after drop column: "))
+}
+
+/// Drop a column by rebuilding its table, following the 12 steps of
+/// <https://www.sqlite.org/lang_altertable.html#otheralter>. Indexes and
+/// triggers using the column are dropped with it.
+fn rebuild_without_column(
+    con: &Connection,
+    table: &str,
+    column: &str,
+) -> Result<Vec<String>, AppError> {
+    let foreign_keys: bool = con.pragma_query_value(None, "foreign_keys", |r| r.get(0))?;
+    con.execute_batch("PRAGMA foreign_keys = OFF; PRAGMA legacy_alter_table = ON; BEGIN;")?;
+    let res = rebuild_steps(con, table, column);
+    let end = match res {
+        Ok(_) => "COMMIT;",
+        Err(_) => "ROLLBACK;",
+    };
+    let restore = format!(
+        "{end} PRAGMA legacy_alter_table = OFF; PRAGMA foreign_keys = {};",
+        foreign_keys as u8
+    );
+    con.execute_batch(&restore)?;
+    res
+}
+
+fn rebuild_steps(con: &Connection, table: &str, column: &str) -> Result<Vec<String>, AppError> {
+    let create: String = con.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1;",
+        [table],
+        |r| r.get(0),
+    )?;
+    let temp_name = format!("{table}_rebuild");
+    let (new_sql, kept) = create_without_column(&create, &temp_name, column)?;
+
+    // indexes and triggers go with the old table and are made again after
+    let mut stmt = con.prepare(
+        "SELECT type, name, sql FROM sqlite_master
+         WHERE tbl_name = ?1 AND type IN ('index', 'trigger') AND sql IS NOT NULL;",
+    )?;
+    let objects: Vec<(String, String, String)> = stmt
+        .query_map([table], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+    // views aren't checked while the table is rebuilt, so try them after
+    let views = readable_views(con)?;
+
+    let kept = kept
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    con.execute_batch(&new_sql)?;
+    con.execute_batch(&format!(
+        "INSERT INTO {} ({kept}) SELECT {kept} FROM {};",
+        quote_ident(&temp_name),
+        quote_ident(table)
+    ))?;
+    con.execute_batch(&format!("DROP TABLE {};", quote_ident(table)))?;
+    con.execute_batch(&format!(
+        "ALTER TABLE {} RENAME TO {};",
+        quote_ident(&temp_name),
+        quote_ident(table)
+    ))?;
+
+    let mut notes = Vec::new();
+    for (kind, name, sql) in objects {
+        if mentions(&sql, table, column) {
+            notes.push(format!("dropped {kind} {name}"));
+        } else {
+            con.execute_batch(&sql)?;
+        }
+    }
+
+    let readable = readable_views(con)?;
+    let broken: Vec<&str> = views
+        .iter()
+        .filter(|v| !readable.contains(v))
+        .map(|v| v.as_str())
+        .collect();
+    if !broken.is_empty() {
+        return Err(AppError::Schema(format!(
+            "{column} is used by the view {}",
+            broken.join(", ")
+        )));
+    }
+
+    let violations: i64 =
+        con.query_row("SELECT count(*) FROM pragma_foreign_key_check;", [], |r| {
+            r.get(0)
+        })?;
+    if violations > 0 {
+        return Err(AppError::Schema(format!(
+            "dropping {column} breaks {violations} foreign keys"
+        )));
+    }
+    Ok(notes)
+}
+
+/// The names of the views that can be read from.
+fn readable_views(con: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = con.prepare("SELECT name FROM sqlite_master WHERE type = 'view';")?;
+    let views: Vec<String> = stmt
+        .query_map([], |r| r.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(views
+        .into_iter()
+        .filter(|v| {
+            con.prepare(&format!("SELECT * FROM {} LIMIT 0;", quote_ident(v)))
+                .is_ok()
+        })
+        .collect())
+}
+
+/// The `CREATE TABLE` statement of a table without one of its columns and
+/// under a new name, with the names of the columns left. The text of the
+/// statement is edited, so the rest stays as it was written.
+fn create_without_column(
+    sql: &str,
+    name: &str,
+    column: &str,
+) -> Result<(String, Vec<String>), AppError> {
+    let unparsed = |e: &dyn std::fmt::Display| {
+        AppError::Schema(format!("can't parse the table definition: {e}"))
+    };
+    let tokens = Tokenizer::new(&SQLiteDialect {}, sql)
+        .tokenize_with_location()
+        .map_err(|e| unparsed(&e))?;
+    let offset = |location: Location| {
+        let line: usize = sql
+            .split_inclusive('\n')
+            .take(location.line.saturating_sub(1) as usize)
+            .map(str::len)
+            .sum();
+        sql[line..]
+            .char_indices()
+            .nth(location.column.saturating_sub(1) as usize)
+            .map_or(sql.len(), |(i, _)| line + i)
+    };
+
+    // the name is the last word before the column list, maybe with a schema
+    let open = tokens
+        .iter()
+        .position(|t| t.token == Token::LParen)
+        .ok_or_else(|| unparsed(&"no column list"))?;
+    let head: Vec<&TokenWithSpan> = tokens[..open]
+        .iter()
+        .filter(|t| !matches!(t.token, Token::Whitespace(_)))
+        .collect();
+    let (name_start, name_end) = match head.as_slice() {
+        [.., schema, dot, table]
+            if dot.token == Token::Period && matches!(schema.token, Token::Word(_)) =>
+        {
+            (schema.span.start, table.span.end)
+        }
+        [.., table] if matches!(table.token, Token::Word(_)) => (table.span.start, table.span.end),
+        _ => return Err(unparsed(&"no table name")),
+    };
+
+    // column definitions and table constraints are split by the commas
+    // outside of parentheses
+    let mut bounds = vec![offset(tokens[open].span.start)];
+    let mut depth = 0;
+    for t in &tokens[open + 1..] {
+        match t.token {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => {
+                bounds.push(offset(t.span.start));
+                break;
+            }
+            Token::RParen => depth -= 1,
+            Token::Comma if depth == 0 => bounds.push(offset(t.span.start)),
+            _ => (),
+        }
+    }
+    if depth != 0 || bounds.len() < 2 || sql[bounds[bounds.len() - 1]..].starts_with(',') {
+        return Err(unparsed(&"no end to the column list"));
+    }
+
+    let mut items = Vec::new();
+    let mut kept = Vec::new();
+    for pair in bounds.windows(2) {
+        let item = &sql[pair[0] + 1..pair[1]];
+        let item_tokens = Tokenizer::new(&SQLiteDialect {}, item)
+            .tokenize()
+            .map_err(|e| unparsed(&e))?;
+        let first = item_tokens
+            .iter()
+            .find(|t| !matches!(t, Token::Whitespace(_)));
+        let Some(Token::Word(first)) = first else {
+            return Err(unparsed(&item.trim()));
+        };
+        let constraint = first.quote_style.is_none()
+            && matches!(
+                first.keyword,
+                Keyword::CONSTRAINT
+                    | Keyword::PRIMARY
+                    | Keyword::UNIQUE
+                    | Keyword::CHECK
+                    | Keyword::FOREIGN
+            );
+        if constraint {
+            // the columns a foreign key references are another table's
+            let own = match item.to_ascii_uppercase().find("REFERENCES") {
+                Some(end) => &item[..end],
+                None => item,
+            };
+            if mentions(own, name, column) {
+                continue;
+            }
+        } else if first.value.eq_ignore_ascii_case(column) {
+            continue;
+        } else {
+            kept.push(first.value.clone());
+        }
+        items.push(item);
+    }
+    if kept.is_empty() {
+        return Err(AppError::Schema(format!("{column} is the only column")));
+    }
+
+    // the space before the closing parenthesis stays, whatever item it ends
+    let close = bounds[bounds.len() - 1];
+    let last = &sql[bounds[bounds.len() - 2] + 1..close];
+    let new_sql = format!(
+        "{}{}{}{}{}{}",
+        &sql[..offset(name_start)],
+        quote_ident(name),
+        &sql[offset(name_end)..=bounds[0]],
+        items.join(",").trim_end(),
+        &last[last.trim_end().len()..],
+        &sql[close..]
+    );
+    Ok((new_sql, kept))
+}
+
+/// True if the sql uses the name as a column of the table: as a word
+/// outside of strings that doesn't name a table or a function and, if
+/// qualified, is qualified by the table or by a trigger's `new` or `old`.
+fn mentions(sql: &str, table: &str, column: &str) -> bool {
+    let Ok(tokens) = Tokenizer::new(&SQLiteDialect {}, sql).tokenize() else {
+        // better to drop an object than to fail rebuilding it
+        return true;
+    };
+    let tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| !matches!(t, Token::Whitespace(_)))
+        .collect();
+    let word = |i: usize| match tokens.get(i) {
+        Some(Token::Word(w)) => Some(w),
+        _ => None,
+    };
+    let keyword = |i: usize| match word(i) {
+        Some(w) if w.quote_style.is_none() => w.keyword,
+        _ => Keyword::NoKeyword,
+    };
+    (0..tokens.len()).any(|i| {
+        if !word(i).is_some_and(|w| w.value.eq_ignore_ascii_case(column)) {
+            return false;
+        }
+        let next = tokens.get(i + 1);
+        if next == Some(&&Token::Period) {
+            return false;
+        }
+        if next == Some(&&Token::LParen) && keyword(i) != Keyword::NoKeyword {
+            return false;
+        }
+        if i == 0 {
+            return true;
+        }
+        if tokens[i - 1] == &Token::Period {
+            let qualifier = i.checked_sub(2).and_then(word);
+            return qualifier.is_some_and(|q| {
+                [table, "new", "old"]
+                    .iter()
+                    .any(|n| q.value.eq_ignore_ascii_case(n))
+            });
+        }
+        match keyword(i - 1) {
+            Keyword::TABLE
+            | Keyword::INTO
+            | Keyword::UPDATE
+            | Keyword::FROM
+            | Keyword::JOIN
+            | Keyword::INDEX
+            | Keyword::TRIGGER
+            | Keyword::VIEW
+            | Keyword::EXISTS => false,
+            // the table of an index or a trigger, unlike a join condition
+            Keyword::ON => {
+                let table_end = matches!(
+                    keyword(i + 1),
+                    Keyword::BEGIN | Keyword::FOR | Keyword::WHEN
+                );
+                !(table_end || matches!(next, None | Some(Token::LParen | Token::SemiColon)))
+            }
+            _ => true,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database(sql: &str) -> Connection {
+        let con = Connection::open_in_memory().unwrap();
+        con.execute_batch(sql).unwrap();
+        con
+    }
+
+    fn drop_column(con: &Connection, column: &str) -> Result<Vec<String>, AppError> {
+        apply(
+            con,
+            &SchemaChange::DropColumn {
+                table: "t".to_string(),
+                column: column.to_string(),
+            },
+        )
+    }
+
+    fn table_sql(con: &Connection) -> String {
+        con.query_row("SELECT sql FROM sqlite_master WHERE name = 't';", [], |r| {
+            r.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn drops_indexed_column_by_rebuilding() {
+        let con = database(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a, b);
+             CREATE INDEX t_a ON t (a);
+             CREATE INDEX t_b ON t (b);
+             INSERT INTO t VALUES (1, 'a', 'b');",
+        );
+        let notes = drop_column(&con, "a").unwrap();
+        assert_eq!(notes, vec!["dropped index t_a"]);
+        let row: (i64, String) = con
+            .query_row("SELECT * FROM t;", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!(row, (1, "b".to_string()));
+        let indexes: i64 = con
+            .query_row(
+                "SELECT count(*) FROM sqlite_master WHERE name = 't_b';",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(indexes, 1);
+    }
+
+    #[test]
+    fn keeps_view_using_column() {
+        let con = database(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a, b);
+             CREATE VIEW v AS SELECT a FROM t;",
+        );
+        assert!(drop_column(&con, "a").is_err());
+        assert!(table_sql(&con).contains(" a,"));
+    }
+
+    #[test]
+    fn rebuilds_without_view_using_column() {
+        let con = database(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, a UNIQUE, b);
+             CREATE VIEW v AS SELECT t.a FROM t;",
+        );
+        let Err(AppError::Schema(e)) = drop_column(&con, "a") else {
+            panic!("dropped a column a view uses");
+        };
+        assert_eq!(e, "a is used by the view v");
+        assert!(table_sql(&con).contains("a UNIQUE"));
+    }
+
+    #[test]
+    fn keeps_table_text() {
+        let sql = "CREATE TABLE main.t ( -- people\n  id INTEGER PRIMARY KEY,\n  \"first name\" TEXT DEFAULT 'a, b',\n  age INT CHECK (age > 0),\n  UNIQUE (\"first name\"),\n  CHECK (age < 200)\n)";
+        let (new_sql, kept) = create_without_column(sql, "t_rebuild", "age").unwrap();
+        assert_eq!(
+            new_sql,
+            "CREATE TABLE \"t_rebuild\" ( -- people\n  id INTEGER PRIMARY KEY,\n  \"first name\" TEXT DEFAULT 'a, b',\n  UNIQUE (\"first name\")\n)"
+        );
+        assert_eq!(kept, vec!["id", "first name"]);
+        let (new_sql, _) = create_without_column(sql, "t_rebuild", "first name").unwrap();
+        assert_eq!(
+            new_sql,
+            "CREATE TABLE \"t_rebuild\" ( -- people\n  id INTEGER PRIMARY KEY,\n  age INT CHECK (age > 0),\n  CHECK (age < 200)\n)"
+        );
+    }
+
+    #[test]
+    fn mentions_only_columns() {
+        assert!(mentions("CREATE INDEX i ON t (a)", "t", "a"));
+        assert!(mentions(
+            "CREATE INDEX i ON t (b) WHERE \"a\" > 0",
+            "t",
+            "a"
+        ));
+        assert!(!mentions("CREATE INDEX i ON a (b)", "a", "a"));
+        assert!(!mentions("CREATE INDEX i ON t (b) WHERE b = 'a'", "t", "a"));
+        assert!(!mentions("CREATE INDEX i ON t (lower(b))", "t", "lower"));
+        let trigger = "CREATE TRIGGER r AFTER INSERT ON t BEGIN
+            INSERT INTO a (x) SELECT a.y FROM a JOIN t ON t.id = a.id WHERE new.b; END";
+        assert!(!mentions(trigger, "t", "a"));
+        assert!(mentions(trigger, "t", "b"));
+        assert!(mentions(trigger, "t", "id"));
+        assert!(!mentions(trigger, "t", "y"));
+    }
+}
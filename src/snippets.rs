@@ -0,0 +1,213 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+/// Appended to the database path to name its snippets file
+const SNIPPETS_SUFFIX: &str = ".queries.toml";
+
+/// Named queries saved for a database.
+///
+/// They live in a toml file next to the database, mapping each name to its
+/// sql, so they can be committed and shared along with it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Snippets {
+    queries: BTreeMap<String, String>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Snippets {
+    /// Load the snippets of a database, a missing file gives none.
+    pub fn load(db_path: &str) -> Result<Snippets, AppError> {
+        let path = PathBuf::from(format!("{db_path}{SNIPPETS_SUFFIX}"));
+        let mut snippets = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|e| AppError::Config(format!("{}: {e}", path.display())))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Snippets::default(),
+            Err(e) => return Err(e.into()),
+        };
+        snippets.path = path;
+        Ok(snippets)
+    }
+
+    /// Names of all snippets, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.queries.keys().cloned().collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.queries.get(name).map(String::as_str)
+    }
+
+    /// Save a query under a name, replacing any query of that name, and
+    /// write the file.
+    pub fn save(&mut self, name: &str, sql: &str) -> Result<(), AppError> {
+        self.queries.insert(name.to_string(), sql.to_string());
+        let text = toml::to_string(self).map_err(|e| AppError::Config(e.to_string()))?;
+        fs::write(&self.path, text)?;
+        Ok(())
+    }
+}
+
+/// Names of the `:name` parameters in a query, in order of first use.
+/// Parameters inside string literals, quoted identifiers and comments are
+/// not counted.
+pub fn parameters(sql: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for_each_parameter(sql, |name| {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        None
+    });
+    names
+}
+
+/// Replace every `:name` parameter with a sql literal of its value. Plain
+/// numbers and `NULL` are used as they are, anything else becomes a string
+/// literal of the text as typed.
+pub fn bind(sql: &str, values: &BTreeMap<String, String>) -> String {
+    for_each_parameter(sql, |name| values.get(name).map(|v| literal(v)))
+}
+
+/// A value as a sql literal, see [`bind`]. Only numbers sqlite reads back
+/// as the same text are plain, so `007`, `1e3`, `+1` or ` 42 ` stay strings.
+pub fn literal(value: &str) -> String {
+    if is_plain_number(value) || value.eq_ignore_ascii_case("null") {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+/// True for an integer that fits 64 bits, or a decimal, without a leading
+/// zero, sign other than minus, exponent or spaces.
+fn is_plain_number(value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits, None),
+    };
+    let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    all_digits(whole)
+        && (whole == "0" || !whole.starts_with('0'))
+        && match fraction {
+            Some(fraction) => all_digits(fraction),
+            None => value.parse::<i64>().is_ok(),
+        }
+}
+
+/// Walk the sql calling `f` for each parameter name, the returned text
+/// replaces the parameter. Returns the resulting sql.
+fn for_each_parameter(sql: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                // copy the quoted text, doubled quotes just reopen it
+                out.push(c);
+                for (_, q) in chars.by_ref() {
+                    out.push(q);
+                    if q == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek().is_some_and(|(_, n)| *n == '-') => {
+                out.push(c);
+                for (_, n) in chars.by_ref() {
+                    out.push(n);
+                    if n == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().is_some_and(|(_, n)| *n == '*') => {
+                out.push(c);
+                // take the opening '*' first, it can't also close `/*/`
+                if let Some((_, star)) = chars.next() {
+                    out.push(star);
+                }
+                let mut previous = ' ';
+                for (_, n) in chars.by_ref() {
+                    out.push(n);
+                    if previous == '*' && n == '/' {
+                        break;
+                    }
+                    previous = n;
+                }
+            }
+            ':' if chars
+                .peek()
+                .is_some_and(|(_, n)| n.is_alphabetic() || *n == '_') =>
+            {
+                let start = i + 1;
+                let mut end = start;
+                while let Some((j, n)) = chars.peek() {
+                    if !(n.is_alphanumeric() || *n == '_') {
+                        break;
+                    }
+                    end = j + n.len_utf8();
+                    chars.next();
+                }
+                let name = &sql[start..end];
+                match f(name) {
+                    Some(replacement) => out.push_str(&replacement),
+                    None => out.push_str(&sql[i..end]),
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_parameters_outside_quotes_and_comments() {
+        let sql = "SELECT ':skip', \":skip\", `:skip` FROM t -- :skip
+            WHERE a = :a /* :skip */ AND b = :b_2 OR a = :a /*/ :skip */";
+        assert_eq!(parameters(sql), ["a", "b_2"]);
+        assert!(parameters("SELECT 1").is_empty());
+    }
+
+    #[test]
+    fn binds_every_use() {
+        let values = BTreeMap::from([
+            ("a".to_string(), "42".to_string()),
+            ("name".to_string(), "O'Brien".to_string()),
+        ]);
+        assert_eq!(
+            bind(
+                "SELECT ':a' WHERE a = :a OR b = :a AND n = :name AND x = :missing",
+                &values
+            ),
+            "SELECT ':a' WHERE a = 42 OR b = 42 AND n = 'O''Brien' AND x = :missing"
+        );
+    }
+
+    #[test]
+    fn literal_keeps_text_as_typed() {
+        for number in ["42", "-7", "0", "1.5", "-0.25", "1.0", "NULL", "null"] {
+            assert_eq!(literal(number), number);
+        }
+        for text in [
+            "007",
+            "1e3",
+            "+1",
+            " 42 ",
+            "1.",
+            ".5",
+            "-",
+            "99999999999999999999",
+        ] {
+            assert_eq!(literal(text), format!("'{text}'"));
+        }
+        assert_eq!(literal("it's"), "'it''s'");
+    }
+}
@@ -1,4 +1,7 @@
-use crate::app::{load_files, App};
+use crate::{
+    app::{load_files, App},
+    error::AppError,
+};
 use colors::*;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -10,13 +13,31 @@ use ratatui::{
 };
 use std::io;
 use string_list::StringList;
-use table_view::TableView;
+use table_view::{EditRequest, TableView};
 
+pub mod blob_viewer;
 pub mod colors;
+pub mod column_chooser;
+pub mod columns;
+pub mod diff_view;
+pub mod error_popup;
 pub mod file_menu;
+pub mod formats;
 pub mod help_view;
+pub mod history_search;
+pub mod index_advisor;
+pub mod index_designer;
+pub mod input;
+pub mod maintenance;
+pub mod overview;
+pub mod plan_view;
+pub mod pragma_panel;
+pub mod schema_menu;
+pub mod space_view;
 pub mod string_list;
+pub mod table_designer;
 pub mod table_view;
+pub mod text_viewer;
 pub mod utils;
 
 const APP_NAME: &str = " JDbrowser ";
@@ -25,6 +46,9 @@ pub struct Ui {
     file_list: StringList,
     pub table_view: TableView,
     show_help: bool,
+    /// Errors waiting to be dismissed by the user, oldest first
+    errors: Vec<String>,
+    quit: bool,
 }
 
 impl Ui {
@@ -35,9 +59,21 @@ impl Ui {
             file_list,
             table_view: TableView::default(),
             show_help: false,
+            errors: Vec::default(),
+            quit: false,
         })
     }
 
+    /// True once the user has asked to exit the application.
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Queue an error to be shown in a popup.
+    pub fn report_error(&mut self, err: AppError) {
+        self.errors.push(err.to_string());
+    }
+
     pub fn ui(&mut self, frame: &mut Frame, app: &mut App) {
         let lay = Layout::horizontal([Constraint::Fill(1)])
             .margin(1)
@@ -52,35 +88,79 @@ impl Ui {
         if self.show_help {
             help_view::draw_help_window(frame, lay[0]);
         }
+
+        if let Some(err) = self.errors.first() {
+            error_popup::draw_error_popup(frame, lay[0], err, self.errors.len() - 1);
+        }
+    }
+
+    /// Regular update independent of input, picks up results of background
+    /// work. Work failing on every tick shows its error once until dismissed.
+    pub fn tick(&mut self, app: &mut App) {
+        if app.current_db.is_some() {
+            if let Err(e) = self.table_view.tick(app) {
+                let err = e.to_string();
+                if !self.errors.contains(&err) {
+                    self.errors.push(err);
+                }
+            }
+        }
+    }
+
+    /// Text the user asked to edit in the external editor, the caller hands
+    /// the terminal over to the editor and passes the result back to
+    /// [`Ui::finish_edit`].
+    pub fn take_edit_request(&mut self) -> Option<EditRequest> {
+        self.table_view.edit_request.take()
     }
 
-    pub fn handle_input(
+    pub fn finish_edit(
         &mut self,
-        key: &KeyEvent,
         app: &mut App,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(_db) = &app.current_db {
-            self.table_view.handle_input(key, app)?;
+        request: EditRequest,
+        edited: Result<String, AppError>,
+    ) {
+        let res = edited.and_then(|text| self.table_view.finish_edit(app, request, text));
+        if let Err(e) = res {
+            self.report_error(e);
+        }
+    }
+
+    /// Handle a key press. Errors are never returned to the caller, they are
+    /// queued and shown to the user instead.
+    pub fn handle_input(&mut self, key: &KeyEvent, app: &mut App) {
+        // an open error popup swallows input until dismissed
+        if !self.errors.is_empty() {
+            if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+                self.errors.remove(0);
+            }
+            return;
+        }
+        let capturing = self.table_view.is_capturing_input();
+        if key.code == KeyCode::Esc && !capturing {
+            self.quit = true;
+            return;
+        }
+
+        app.status = None;
+        let res = if let Some(_db) = &app.current_db {
+            self.table_view.handle_input(key, app)
         } else {
-            self.handle_flist_input(key, app)?;
+            self.handle_flist_input(key, app)
+        };
+        if let Err(e) = res {
+            self.report_error(e);
         }
-        if key.code == KeyCode::Char('?') {
+        if key.code == KeyCode::Char('?') && !capturing {
             self.show_help = !self.show_help;
         }
-        Ok(())
     }
 
-    fn handle_flist_input(
-        &mut self,
-        key: &KeyEvent,
-        app: &mut App,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    fn handle_flist_input(&mut self, key: &KeyEvent, app: &mut App) -> Result<(), AppError> {
         if key.code == KeyCode::Enter {
             if let Some(path) = self.file_list.get_selected() {
                 app.load_db(path)?;
-                if let Some(db) = &app.current_db {
-                    self.table_view.load_nav(db);
-                }
+                self.table_view.load_nav(app)?;
             }
         } else if key.code == KeyCode::Char('k') {
             self.file_list.list_state.select_previous();
@@ -119,11 +199,22 @@ fn new_outer_frame<'a>(app: &App, key_binds: Vec<Span<'a>>) -> Block<'a> {
     let mut outer_frame = Block::bordered()
         .title(Line::from(APP_NAME).fg(SECONDARY_COLOR).bold().centered())
         .fg(PRIMARY_COLOR)
-        .border_type(BorderType::Rounded);
-    if let Some(_pat) = &app.current_db {
-        outer_frame = outer_frame.title_bottom(key_instruction);
-    } else {
-        outer_frame = outer_frame.title_bottom(key_instruction);
+        .border_type(BorderType::Rounded)
+        .title_bottom(key_instruction);
+    if app.read_only {
+        outer_frame = outer_frame.title(
+            Line::from(" read-only ")
+                .fg(HIGHLIGHTED_COLOR)
+                .bold()
+                .right_aligned(),
+        );
+    }
+    if let Some(status) = &app.status {
+        outer_frame = outer_frame.title_bottom(
+            Line::from(format!(" {status} "))
+                .fg(TEXT_COLOR)
+                .left_aligned(),
+        );
     }
     outer_frame
 }
@@ -0,0 +1,321 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph},
+    Frame,
+};
+
+use super::{
+    input::{self, TextInput},
+    PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+
+const BYTES_PER_LINE: usize = 16;
+const KEYS: &str = " Scroll [k/j] Page [u/d] Top/Bottom [g/G] Save [s] Close [Esc] ";
+
+/// What the user did in the blob viewer.
+pub enum BlobAction {
+    None,
+    Close,
+    /// Save the blob to the given path
+    Save(String),
+}
+
+/// Popup showing a hex and ascii dump of a blob.
+pub struct BlobViewer {
+    bytes: Vec<u8>,
+    kind: BlobKind,
+    /// First line of the dump shown
+    scroll: usize,
+    /// Lines of the dump that fit on screen, known after drawing
+    height: usize,
+    save_prompt: Option<TextInput>,
+}
+
+impl BlobViewer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            kind: BlobKind::sniff(&bytes),
+            bytes,
+            scroll: 0,
+            height: 0,
+            save_prompt: None,
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> BlobAction {
+        if let Some(input) = &mut self.save_prompt {
+            match key.code {
+                KeyCode::Esc => self.save_prompt = None,
+                KeyCode::Enter => {
+                    let path = input.value.trim().to_string();
+                    self.save_prompt = None;
+                    if !path.is_empty() {
+                        return BlobAction::Save(path);
+                    }
+                }
+                _ => input.handle_key(key),
+            }
+            return BlobAction::None;
+        }
+
+        let last = self.bytes.len().div_ceil(BYTES_PER_LINE).saturating_sub(1);
+        let page = (self.height / 2).max(1);
+        match key.code {
+            KeyCode::Esc => return BlobAction::Close,
+            KeyCode::Char('j') => self.scroll = (self.scroll + 1).min(last),
+            KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Char('d') => self.scroll = (self.scroll + page).min(last),
+            KeyCode::Char('u') => self.scroll = self.scroll.saturating_sub(page),
+            KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::Char('G') => self.scroll = last,
+            KeyCode::Char('s') => {
+                let name = format!("blob.{}", self.kind.extension());
+                self.save_prompt = Some(TextInput::new(&name));
+            }
+            _ => {}
+        }
+        BlobAction::None
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let title = format!(" Blob - {} bytes - {} ", self.bytes.len(), self.kind);
+        let block = Block::bordered()
+            .title(Line::from(title).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(KEYS).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let [inner] = Layout::vertical([Constraint::Fill(1)])
+            .margin(1)
+            .areas(block.inner(lay));
+        self.height = inner.height as usize;
+
+        let lines: Vec<Line> = self
+            .bytes
+            .chunks(BYTES_PER_LINE)
+            .enumerate()
+            .skip(self.scroll)
+            .take(self.height)
+            .map(|(i, chunk)| dump_line(i * BYTES_PER_LINE, chunk))
+            .collect();
+
+        frame.render_widget(Clear, lay);
+        frame.render_widget(block, lay);
+        frame.render_widget(Paragraph::new(lines), inner);
+
+        if let Some(input) = &self.save_prompt {
+            input::draw_prompt(frame, lay, "Save blob to file", input);
+        }
+    }
+}
+
+/// One line of the dump: offset, hex bytes and their ascii characters.
+fn dump_line<'a>(offset: usize, chunk: &[u8]) -> Line<'a> {
+    let mut hex = String::with_capacity(BYTES_PER_LINE * 3 + 1);
+    for i in 0..BYTES_PER_LINE {
+        if i == BYTES_PER_LINE / 2 {
+            hex.push(' ');
+        }
+        match chunk.get(i) {
+            Some(b) => hex.push_str(&format!("{b:02x} ")),
+            None => hex.push_str("   "),
+        }
+    }
+    let ascii: String = chunk
+        .iter()
+        .map(|b| match b {
+            0x20..=0x7e => *b as char,
+            _ => '.',
+        })
+        .collect();
+    Line::from(vec![
+        Span::from(format!("{offset:08x}  ")).fg(SECONDARY_COLOR),
+        Span::from(hex).fg(TEXT_COLOR),
+        Span::from(format!(" |{ascii}|")).fg(PRIMARY_COLOR),
+    ])
+}
+
+/// Content type of a blob guessed from its first bytes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlobKind {
+    Png,
+    Jpeg,
+    Gif,
+    Pdf,
+    Zip,
+    Gzip,
+    Zlib,
+    Sqlite,
+    Protobuf,
+    Text,
+    Binary,
+}
+
+impl BlobKind {
+    pub fn sniff(bytes: &[u8]) -> BlobKind {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            BlobKind::Png
+        } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            BlobKind::Jpeg
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            BlobKind::Gif
+        } else if bytes.starts_with(b"%PDF") {
+            BlobKind::Pdf
+        } else if bytes.starts_with(b"PK\x03\x04") {
+            BlobKind::Zip
+        } else if bytes.starts_with(&[0x1f, 0x8b]) {
+            BlobKind::Gzip
+        } else if bytes.starts_with(b"SQLite format 3\0") {
+            BlobKind::Sqlite
+        } else if is_zlib(bytes) {
+            BlobKind::Zlib
+        } else if is_text(bytes) {
+            BlobKind::Text
+        } else if is_protobuf(bytes) {
+            BlobKind::Protobuf
+        } else {
+            BlobKind::Binary
+        }
+    }
+
+    /// File extension used when saving a blob of this kind.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BlobKind::Png => "png",
+            BlobKind::Jpeg => "jpg",
+            BlobKind::Gif => "gif",
+            BlobKind::Pdf => "pdf",
+            BlobKind::Zip => "zip",
+            BlobKind::Gzip => "gz",
+            BlobKind::Zlib => "zz",
+            BlobKind::Sqlite => "db",
+            BlobKind::Protobuf => "pb",
+            BlobKind::Text => "txt",
+            BlobKind::Binary => "bin",
+        }
+    }
+}
+
+impl std::fmt::Display for BlobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BlobKind::Png => "PNG image",
+            BlobKind::Jpeg => "JPEG image",
+            BlobKind::Gif => "GIF image",
+            BlobKind::Pdf => "PDF document",
+            BlobKind::Zip => "zip archive",
+            BlobKind::Gzip => "gzip data",
+            BlobKind::Zlib => "zlib data",
+            BlobKind::Sqlite => "sqlite database",
+            BlobKind::Protobuf => "protobuf message (probably)",
+            BlobKind::Text => "UTF-8 text",
+            BlobKind::Binary => "binary data",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// zlib streams start with a deflate method byte and a header checksum.
+fn is_zlib(bytes: &[u8]) -> bool {
+    match bytes {
+        [cmf, flg, ..] => {
+            cmf & 0x0f == 8 && cmf >> 4 <= 7 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0
+        }
+        _ => false,
+    }
+}
+
+/// Valid UTF-8 made of printable characters and whitespace.
+fn is_text(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => !s.is_empty() && s.chars().all(|c| !c.is_control() || c.is_whitespace()),
+        Err(_) => false,
+    }
+}
+
+/// True if the bytes parse as a sequence of protobuf fields.
+fn is_protobuf(bytes: &[u8]) -> bool {
+    fn varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = *bytes.get(*pos)?;
+            *pos += 1;
+            value |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    let mut pos = 0;
+    let mut fields = 0;
+    while pos < bytes.len() {
+        let Some(key) = varint(bytes, &mut pos) else {
+            return false;
+        };
+        if key >> 3 == 0 {
+            return false;
+        }
+        let skip = match key & 7 {
+            0 => varint(bytes, &mut pos).map(|_| 0),
+            1 => Some(8),
+            2 => varint(bytes, &mut pos).and_then(|len| usize::try_from(len).ok()),
+            5 => Some(4),
+            _ => None,
+        };
+        // lengths read from the blob can be anything, up to u64::MAX
+        match skip.and_then(|n| pos.checked_add(n)) {
+            Some(end) if end <= bytes.len() => pos = end,
+            _ => return false,
+        }
+        fields += 1;
+    }
+    fields > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_blob_kinds() {
+        let kinds = [
+            (&b"\x89PNG\r\n\x1a\n...."[..], BlobKind::Png),
+            (&[0xff, 0xd8, 0xff, 0xe0], BlobKind::Jpeg),
+            (b"GIF89a..", BlobKind::Gif),
+            (b"%PDF-1.7", BlobKind::Pdf),
+            (b"PK\x03\x04..", BlobKind::Zip),
+            (&[0x1f, 0x8b, 0x08, 0x00], BlobKind::Gzip),
+            (&[0x78, 0x9c, 0x03, 0x00], BlobKind::Zlib),
+            (b"SQLite format 3\0....", BlobKind::Sqlite),
+            (b"hello\n\tworld", BlobKind::Text),
+            // field 1 varint 150, field 2 string "hi"
+            (
+                &[0x08, 0x96, 0x01, 0x12, 0x02, b'h', b'i'],
+                BlobKind::Protobuf,
+            ),
+            (&[0x00, 0x01, 0x02], BlobKind::Binary),
+            (&[], BlobKind::Binary),
+        ];
+        for (bytes, kind) in kinds {
+            assert_eq!(BlobKind::sniff(bytes), kind, "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_protobuf_running_past_the_end() {
+        // a length far beyond the blob, and one that overflows the position
+        assert_eq!(BlobKind::sniff(&[0x0a, 0x7f, 0x00]), BlobKind::Binary);
+        let huge = [
+            0x0a, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+        ];
+        assert_eq!(BlobKind::sniff(&huge), BlobKind::Binary);
+    }
+}
@@ -5,4 +5,11 @@ pub const SECONDARY_COLOR: Color = Color::Yellow;
 pub const TEXT_COLOR: Color = Color::White;
 pub const HIGHLIGHTED_COLOR: Color = Color::Green;
 
+pub const NULL_COLOR: Color = Color::DarkGray;
+pub const NUMBER_COLOR: Color = Color::Cyan;
+pub const BLOB_COLOR: Color = Color::Magenta;
+pub const FORMATTED_COLOR: Color = Color::LightYellow;
+/// Background of rows that changed on disk
+pub const CHANGED_COLOR: Color = Color::Indexed(22);
+
 pub const HIGHLIGHT_STYLE: Style = Style::new().fg(HIGHLIGHTED_COLOR);
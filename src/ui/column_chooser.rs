@@ -0,0 +1,123 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use super::{utils::center, HIGHLIGHT_STYLE, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR};
+use crate::layouts::ColumnLayout;
+
+const TITLE: &str = " Columns ";
+const KEYS: &str = " Show/Hide [Space] Move [K/J] Pin [p] Apply [Enter] Cancel [Esc] ";
+
+/// What the user did in the column chooser.
+pub enum ChooserAction {
+    None,
+    Apply(ColumnLayout),
+    Cancel,
+}
+
+/// Popup to hide, reorder and pin the columns of a table.
+pub struct ColumnChooser {
+    /// Every column of the table in layout order, with whether it is shown
+    items: Vec<(String, bool)>,
+    /// Number of leading shown columns that are pinned
+    pinned: usize,
+    list_state: ListState,
+}
+
+impl ColumnChooser {
+    pub fn new(layout: &ColumnLayout, columns: &[String]) -> Self {
+        Self {
+            items: layout.arrange(columns),
+            pinned: layout.pinned,
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> ChooserAction {
+        let selected = self.list_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => return ChooserAction::Cancel,
+            KeyCode::Enter => return ChooserAction::Apply(self.to_layout()),
+            KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Char('k') => self.list_state.select_previous(),
+            KeyCode::Char(' ') => {
+                // keep at least one column shown
+                let shown = self.items.iter().filter(|(_, s)| *s).count();
+                if let Some((_, show)) = self.items.get_mut(selected) {
+                    if !*show || shown > 1 {
+                        *show = !*show;
+                    }
+                }
+            }
+            KeyCode::Char('K') if selected > 0 => {
+                self.items.swap(selected, selected - 1);
+                self.list_state.select_previous();
+            }
+            KeyCode::Char('J') if selected + 1 < self.items.len() => {
+                self.items.swap(selected, selected + 1);
+                self.list_state.select_next();
+            }
+            KeyCode::Char('p') => {
+                // pin every shown column up to and including this one
+                let upto = self.items[..=selected.min(self.items.len().saturating_sub(1))]
+                    .iter()
+                    .filter(|(_, s)| *s)
+                    .count();
+                self.pinned = if self.pinned == upto { 0 } else { upto };
+            }
+            _ => {}
+        }
+        ChooserAction::None
+    }
+
+    fn to_layout(&self) -> ColumnLayout {
+        let shown = self.items.iter().filter(|(_, s)| *s).count();
+        ColumnLayout {
+            order: self.items.iter().map(|(c, _)| c.clone()).collect(),
+            hidden: self
+                .items
+                .iter()
+                .filter(|(_, s)| !*s)
+                .map(|(c, _)| c.clone())
+                .collect(),
+            pinned: self.pinned.min(shown),
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let mut shown = 0;
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .map(|(name, show)| {
+                let check = if *show { "[x]" } else { "[ ]" };
+                let mut line = Line::from(format!("{check} {name}")).fg(TEXT_COLOR);
+                if *show {
+                    shown += 1;
+                    if shown <= self.pinned {
+                        line.push_span(" (pinned)".fg(SECONDARY_COLOR));
+                    }
+                }
+                ListItem::from(line)
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(HIGHLIGHT_STYLE)
+            .highlight_symbol(">")
+            .block(
+                Block::bordered()
+                    .title(Line::from(TITLE).fg(SECONDARY_COLOR).bold().centered())
+                    .title_bottom(Line::from(KEYS).fg(SECONDARY_COLOR).centered())
+                    .fg(PRIMARY_COLOR)
+                    .border_type(BorderType::Rounded),
+            );
+        let area = center(lay, Constraint::Length(72), Constraint::Percentage(70));
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+}
@@ -0,0 +1,201 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Widest a column grows to before its values are truncated.
+pub const DEFAULT_MAX_COLUMN_WIDTH: usize = 40;
+/// Narrowest width a column can be resized to.
+const MIN_COLUMN_WIDTH: usize = 3;
+const ELLIPSIS: char = '…';
+
+/// Widths, frozen columns and horizontal scroll of the data table.
+///
+/// Widths are kept per table and only grow, so columns don't jump around
+/// when moving between pages.
+#[derive(Debug)]
+pub struct Columns {
+    /// Table the widths were measured for
+    table: String,
+    /// Column names the widths were measured for
+    header: Vec<String>,
+    widths: Vec<usize>,
+    /// Columns resized by the user, these are not measured any more
+    manual: Vec<bool>,
+    /// Number of leading columns that stay visible when scrolling right
+    pub frozen: usize,
+    /// First scrollable column shown
+    offset: usize,
+    pub max_width: usize,
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Self {
+            table: String::default(),
+            header: Vec::default(),
+            widths: Vec::default(),
+            manual: Vec::default(),
+            frozen: 0,
+            offset: 0,
+            max_width: DEFAULT_MAX_COLUMN_WIDTH,
+        }
+    }
+}
+
+impl Columns {
+    /// Forget widths and frozen columns when a different table is shown.
+    pub fn reset_for(&mut self, table: &str) {
+        if self.table != table {
+            self.table = table.to_string();
+            self.widths.clear();
+            self.manual.clear();
+            self.frozen = 0;
+            self.offset = 0;
+        }
+    }
+
+    /// Grow column widths to fit the given header and rows.
+    pub fn measure<S: AsRef<str>>(&mut self, header: &[String], rows: &[Vec<S>]) {
+        if self.header != header {
+            // columns were hidden or moved, widths no longer line up
+            self.header = header.to_vec();
+            self.widths.clear();
+            self.manual.clear();
+        }
+        self.widths.resize(header.len(), 0);
+        self.manual.resize(header.len(), false);
+        let header = header.iter().map(|h| display_width(h));
+        let values = rows
+            .iter()
+            .flat_map(|row| row.iter().map(|v| display_width(v.as_ref())).enumerate());
+        for (i, w) in header.enumerate().chain(values) {
+            if i < self.widths.len() && !self.manual[i] {
+                self.widths[i] = self.widths[i].max(w.min(self.max_width));
+            }
+        }
+    }
+
+    pub fn width(&self, col: usize) -> usize {
+        self.widths.get(col).copied().unwrap_or(0)
+    }
+
+    /// Widen (or with a negative amount narrow) a column.
+    pub fn resize(&mut self, col: usize, by: isize) {
+        if let Some(w) = self.widths.get_mut(col) {
+            *w = w.saturating_add_signed(by).max(MIN_COLUMN_WIDTH);
+            self.manual[col] = true;
+        }
+    }
+
+    /// Freeze all columns up to and including `col`, or unfreeze them if
+    /// they already are.
+    pub fn toggle_freeze(&mut self, col: usize) {
+        self.frozen = if self.frozen == col + 1 { 0 } else { col + 1 };
+        self.offset = self.offset.max(self.frozen);
+    }
+
+    /// Columns to draw in the available width, frozen columns first, then
+    /// scrollable ones starting from the offset, which is moved so that the
+    /// selected column is always shown.
+    pub fn visible(&mut self, selected: usize, available: usize, spacing: usize) -> Vec<usize> {
+        let count = self.widths.len();
+        // the selection may be left over from a table with more columns
+        let selected = selected.min(count.saturating_sub(1));
+        let frozen = self.frozen.min(count);
+        let frozen_width: usize = (0..frozen).map(|c| self.widths[c] + spacing).sum();
+        let available = available.saturating_sub(frozen_width);
+
+        self.offset = self.offset.clamp(frozen, count.max(frozen));
+        if selected >= frozen {
+            if selected < self.offset {
+                self.offset = selected;
+            }
+            // scroll right until the selected column fits
+            while self.offset < selected
+                && (self.offset..=selected)
+                    .map(|c| self.widths[c] + spacing)
+                    .sum::<usize>()
+                    > available
+            {
+                self.offset += 1;
+            }
+        }
+
+        let mut visible: Vec<usize> = (0..frozen).collect();
+        let mut used = 0;
+        for col in self.offset..count {
+            used += self.widths[col] + spacing;
+            if used > available && col > self.offset {
+                break;
+            }
+            visible.push(col);
+        }
+        visible
+    }
+}
+
+/// Width of a value when displayed in a single cell.
+pub fn display_width(value: &str) -> usize {
+    value.lines().map(|l| l.width()).max().unwrap_or(0)
+}
+
+/// Cut a value down to the given width, marking the cut with an ellipsis.
+pub fn truncate(value: &str, width: usize) -> String {
+    if display_width(value) <= width {
+        return value.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in value.chars() {
+        let w = c.width().unwrap_or(0);
+        if c == '\n' || used + w >= width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push(ELLIPSIS);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Columns of the given widths.
+    fn columns(widths: &[usize]) -> Columns {
+        let header: Vec<String> = widths.iter().map(|w| "x".repeat(*w)).collect();
+        let mut columns = Columns::default();
+        columns.measure::<String>(&header, &[]);
+        columns
+    }
+
+    #[test]
+    fn scrolls_to_the_selected_column() {
+        let mut columns = columns(&[4, 4, 4, 4, 4]);
+        assert_eq!(columns.visible(0, 12, 1), [0, 1]);
+        assert_eq!(columns.visible(3, 12, 1), [2, 3]);
+        // moving left within the shown columns doesn't scroll
+        assert_eq!(columns.visible(2, 12, 1), [2, 3]);
+        assert_eq!(columns.visible(0, 12, 1), [0, 1]);
+    }
+
+    #[test]
+    fn keeps_frozen_columns() {
+        let mut columns = columns(&[4, 4, 4, 4, 4]);
+        columns.toggle_freeze(0);
+        assert_eq!(columns.visible(4, 12, 1), [0, 4]);
+        assert_eq!(columns.visible(0, 12, 1), [0, 4]);
+        columns.toggle_freeze(0);
+        assert_eq!(columns.visible(0, 12, 1), [0, 1]);
+    }
+
+    #[test]
+    fn shows_something_whatever_the_selection() {
+        // a selection left over from a table with more columns
+        let mut narrow = columns(&[4, 4]);
+        assert_eq!(narrow.visible(7, 12, 1), [0, 1]);
+        // a column wider than the screen
+        let mut wide = columns(&[30, 4]);
+        assert_eq!(wide.visible(0, 12, 1), [0]);
+        assert!(Columns::default().visible(3, 12, 1).is_empty());
+    }
+}
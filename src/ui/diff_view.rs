@@ -0,0 +1,404 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    sync::mpsc::{Receiver, TryRecvError},
+    time::Instant,
+};
+
+use super::{
+    input::TextInput, utils::center, FORMATTED_COLOR, HIGHLIGHTED_COLOR, HIGHLIGHT_STYLE,
+    NULL_COLOR, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+use crate::{
+    app::App,
+    diff::{self, Change, DbDiff, TableDiff},
+    error::AppError,
+};
+
+const RUNNING_KEYS: &str = " Comparing, please wait ";
+const KEYS: &str = " Select [k/j] Scroll [u/d] Go to Table [Enter] Write Patch [w] Close [Esc] ";
+const SAVE_KEYS: &str = " Write [Enter] Cancel [Esc] ";
+/// Rows and objects only in the first database
+const REMOVED_COLOR: Color = Color::Red;
+/// Lines the details scroll by
+const SCROLL_STEP: u16 = 5;
+
+/// What the user did in the diff view.
+pub enum DiffAction {
+    None,
+    /// Show the given table
+    GoTo(String),
+    /// Write the patch to the given path
+    WritePatch(String),
+    Close,
+}
+
+/// A table or view that differs, by position in the diff.
+enum Entry {
+    Table(usize),
+    View(usize),
+}
+
+enum State {
+    Running {
+        started: Instant,
+        rx: Receiver<Result<DbDiff, AppError>>,
+    },
+    Done {
+        diff: DbDiff,
+        entries: Vec<Entry>,
+        list_state: ListState,
+        scroll: u16,
+        /// Path the patch is about to be written to
+        saving: Option<TextInput>,
+    },
+}
+
+/// Popup comparing the open database with another, table by table, and
+/// writing the patch turning one into the other.
+pub struct DiffView {
+    other: String,
+    /// Table to select once the comparison is done
+    selected: Option<String>,
+    state: State,
+}
+
+impl DiffView {
+    /// Compare in the background, see [`DiffView::poll`].
+    pub fn start(app: &App, other: &str, selected: Option<String>) -> Result<Self, AppError> {
+        Ok(Self {
+            other: other.to_string(),
+            selected,
+            state: State::Running {
+                started: Instant::now(),
+                rx: app.start_diff(other)?,
+            },
+        })
+    }
+
+    /// Check on the running comparison, errors end it.
+    pub fn poll(&mut self) -> Result<(), AppError> {
+        let State::Running { rx, .. } = &self.state else {
+            return Ok(());
+        };
+        let diff = match rx.try_recv() {
+            Ok(res) => res?,
+            Err(TryRecvError::Empty) => return Ok(()),
+            Err(TryRecvError::Disconnected) => {
+                return Err(AppError::Diff("the comparison stopped".to_string()))
+            }
+        };
+        let mut entries: Vec<Entry> = diff
+            .tables
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.change != Change::Same)
+            .map(|(i, _)| Entry::Table(i))
+            .collect();
+        entries.extend((0..diff.views.len()).map(Entry::View));
+        let selected = entries
+            .iter()
+            .position(|e| match e {
+                Entry::Table(i) => Some(&diff.tables[*i].name) == self.selected.as_ref(),
+                Entry::View(_) => false,
+            })
+            .unwrap_or(0);
+        self.state = State::Done {
+            diff,
+            entries,
+            list_state: ListState::default().with_selected(Some(selected)),
+            scroll: 0,
+            saving: None,
+        };
+        Ok(())
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> DiffAction {
+        let State::Done {
+            diff,
+            entries,
+            list_state,
+            scroll,
+            saving,
+        } = &mut self.state
+        else {
+            // the comparison can't be stopped halfway
+            return DiffAction::None;
+        };
+        if let Some(input) = saving {
+            match key.code {
+                KeyCode::Esc => *saving = None,
+                KeyCode::Enter => {
+                    let path = input.value.trim().to_string();
+                    *saving = None;
+                    if !path.is_empty() {
+                        return DiffAction::WritePatch(path);
+                    }
+                }
+                _ => input.handle_key(key),
+            }
+            return DiffAction::None;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return DiffAction::Close,
+            KeyCode::Char('j') => {
+                list_state.select_next();
+                *scroll = 0;
+            }
+            KeyCode::Char('k') => {
+                list_state.select_previous();
+                *scroll = 0;
+            }
+            KeyCode::Char('d') => *scroll = scroll.saturating_add(SCROLL_STEP),
+            KeyCode::Char('u') => *scroll = scroll.saturating_sub(SCROLL_STEP),
+            KeyCode::Char('w') => {
+                let target = Path::new(&diff.from).with_extension("patch.sql");
+                *saving = Some(TextInput::new(&target.display().to_string()));
+            }
+            KeyCode::Enter => {
+                let selected = list_state.selected().and_then(|i| entries.get(i));
+                if let Some(Entry::Table(i)) = selected {
+                    // tables only in the other database can't be shown
+                    if diff.tables[*i].before.is_some() {
+                        return DiffAction::GoTo(diff.tables[*i].name.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+        DiffAction::None
+    }
+
+    /// Write the patch turning the open database into the other one.
+    /// Returns the number of statements written.
+    pub fn write_patch(&self, path: &str) -> Result<usize, AppError> {
+        let State::Done { diff, .. } = &self.state else {
+            return Ok(0);
+        };
+        let mut out = BufWriter::new(File::create(path)?);
+        let statements = diff::write_patch(diff, &mut out)?;
+        out.flush()?;
+        Ok(statements)
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(85), Constraint::Percentage(80));
+        let keys = match &self.state {
+            State::Running { .. } => RUNNING_KEYS,
+            State::Done {
+                saving: Some(_), ..
+            } => SAVE_KEYS,
+            State::Done { .. } => KEYS,
+        };
+        let title = format!(" Compare with {} ", file_name(&self.other));
+        let block = Block::bordered()
+            .title(Line::from(title).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(keys).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let State::Done {
+            diff,
+            entries,
+            list_state,
+            scroll,
+            saving,
+        } = &mut self.state
+        else {
+            if let State::Running { started, .. } = &self.state {
+                let text = format!(
+                    "Comparing with {}... {}s",
+                    self.other,
+                    started.elapsed().as_secs()
+                );
+                frame.render_widget(Line::from(text).fg(TEXT_COLOR), inner);
+            }
+            return;
+        };
+
+        let [summary_area, main_area, save_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(if saving.is_some() { 2 } else { 0 }),
+        ])
+        .horizontal_margin(1)
+        .areas(inner);
+        let summary = Line::from(vec![
+            Span::from(format!(
+                "{} -> {}: ",
+                file_name(&diff.from),
+                file_name(&diff.to)
+            ))
+            .fg(SECONDARY_COLOR)
+            .bold(),
+            Span::from(diff.summary()).fg(TEXT_COLOR),
+        ]);
+        frame.render_widget(summary, summary_area);
+
+        let [list_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Fill(1)])
+                .spacing(2)
+                .areas(main_area);
+        let items: Vec<ListItem> = entries
+            .iter()
+            .map(|entry| {
+                let (change, name, summary) = match entry {
+                    Entry::Table(i) => {
+                        let table = &diff.tables[*i];
+                        (table.change, table.name.as_str(), table.summary())
+                    }
+                    Entry::View(i) => {
+                        let view = &diff.views[*i];
+                        (view.change, view.name.as_str(), "view".to_string())
+                    }
+                };
+                ListItem::from(Line::from(vec![
+                    Span::from(format!("{} {name} ", change.mark())).fg(color(change)),
+                    Span::from(summary).fg(NULL_COLOR),
+                ]))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(HIGHLIGHT_STYLE)
+                .highlight_symbol(">"),
+            list_area,
+            list_state,
+        );
+
+        let lines = match list_state.selected().and_then(|i| entries.get(i)) {
+            Some(Entry::Table(i)) => table_lines(&diff.tables[*i]),
+            Some(Entry::View(i)) => {
+                let view = &diff.views[*i];
+                statement_lines(view.before.as_deref(), view.after.as_deref())
+            }
+            None => vec![Line::from("The databases are the same").fg(TEXT_COLOR)],
+        };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((*scroll, 0)),
+            details_area,
+        );
+
+        if let Some(input) = saving {
+            let label = "Write patch to: ";
+            let [label_area, input_area] =
+                Layout::horizontal([Constraint::Length(label.len() as u16), Constraint::Fill(1)])
+                    .areas(save_area);
+            frame.render_widget(Line::from(label).fg(SECONDARY_COLOR).bold(), label_area);
+            input.draw(frame, input_area);
+        }
+    }
+}
+
+fn color(change: Change) -> Color {
+    match change {
+        Change::Added => HIGHLIGHTED_COLOR,
+        Change::Removed => REMOVED_COLOR,
+        Change::Changed => FORMATTED_COLOR,
+        Change::Same => TEXT_COLOR,
+    }
+}
+
+/// The name of a database file without its directory.
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or(path.to_string(), |n| n.to_string_lossy().into_owned())
+}
+
+fn heading(text: &str) -> Line<'static> {
+    Line::from(text.to_string()).fg(SECONDARY_COLOR).bold()
+}
+
+/// A statement before and after, as removed and added lines.
+fn statement_lines(before: Option<&str>, after: Option<&str>) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if let Some(before) = before {
+        lines.push(Line::from(format!("- {before}")).fg(REMOVED_COLOR));
+    }
+    if let Some(after) = after {
+        lines.push(Line::from(format!("+ {after}")).fg(HIGHLIGHTED_COLOR));
+    }
+    lines
+}
+
+/// What differs in a table, its schema and then its rows.
+fn table_lines(table: &TableDiff) -> Vec<Line<'static>> {
+    let mut lines = vec![heading("Schema")];
+    match table.change {
+        Change::Added | Change::Removed => {
+            lines.extend(statement_lines(
+                table.before.as_deref(),
+                table.after.as_deref(),
+            ));
+        }
+        _ if table.schema_changed() => {
+            lines.extend(statement_lines(
+                table.before.as_deref(),
+                table.after.as_deref(),
+            ));
+        }
+        _ if table.schema.is_empty() => lines.push(Line::from("same").fg(NULL_COLOR)),
+        _ => {}
+    }
+    for item in &table.schema {
+        let line = format!("{} {}", item.change.mark(), item.describe());
+        lines.push(Line::from(line).fg(color(item.change)));
+        if item.kind != "column" {
+            if let Some(sql) = item.after.as_ref().or(item.before.as_ref()) {
+                lines.push(Line::from(format!("    {sql}")).fg(NULL_COLOR));
+            }
+        }
+    }
+
+    lines.push(Line::default());
+    lines.push(heading("Rows"));
+    let key = match (&table.key, table.change) {
+        (_, Change::Added) => {
+            lines.push(Line::from(format!("{} rows added", table.added)).fg(TEXT_COLOR));
+            return lines;
+        }
+        (_, Change::Removed) => {
+            lines.push(Line::from(format!("{} rows removed", table.removed)).fg(TEXT_COLOR));
+            return lines;
+        }
+        (None, _) => {
+            let text = "Not compared, the primary key differs or this is a virtual table";
+            lines.push(Line::from(text).fg(NULL_COLOR));
+            return lines;
+        }
+        (Some(key), _) => key,
+    };
+    let counts = format!(
+        "{} added, {} removed, {} changed, matched by {}",
+        table.added,
+        table.removed,
+        table.changed,
+        key.join(", ")
+    );
+    lines.push(Line::from(counts).fg(TEXT_COLOR));
+    for row in &table.rows {
+        let line = format!("{} {}", row.change.mark(), row.describe(key));
+        lines.push(Line::from(line).fg(color(row.change)));
+    }
+    let more = table.added + table.removed + table.changed - table.rows.len();
+    if more > 0 {
+        let text = format!("... {more} more rows, the patch has them all");
+        lines.push(Line::from(text).fg(NULL_COLOR));
+    }
+    lines
+}
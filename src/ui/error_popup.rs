@@ -0,0 +1,44 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use super::{utils::center, SECONDARY_COLOR, TEXT_COLOR};
+
+const TITLE: &str = " Error ";
+const DISMISS: &str = " Dismiss [Enter] ";
+const ERROR_COLOR: Color = Color::Red;
+
+/// Draws a dismissible popup with the given error message.
+///
+/// `pending` is the number of further errors queued behind this one.
+pub fn draw_error_popup(frame: &mut Frame, lay: Rect, message: &str, pending: usize) {
+    let area = center(lay, Constraint::Percentage(60), Constraint::Length(9));
+    let mut block = Block::bordered()
+        .title(Line::from(TITLE).fg(ERROR_COLOR).bold().centered())
+        .title_bottom(Line::from(DISMISS).fg(SECONDARY_COLOR).bold().centered())
+        .fg(ERROR_COLOR)
+        .border_type(BorderType::Rounded);
+    if pending > 0 {
+        block = block.title(
+            Line::from(format!(" +{pending} more "))
+                .fg(SECONDARY_COLOR)
+                .right_aligned(),
+        );
+    }
+    let [inner] = Layout::vertical([Constraint::Fill(1)])
+        .margin(1)
+        .areas(block.inner(area));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    frame.render_widget(
+        Paragraph::new(message)
+            .wrap(Wrap { trim: true })
+            .fg(TEXT_COLOR),
+        inner,
+    );
+}
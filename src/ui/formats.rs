@@ -0,0 +1,257 @@
+use crate::value::Value;
+
+/// Words in a declared column type that mark it as holding dates.
+const DATE_TYPES: [&str; 6] = [
+    "date",
+    "datetime",
+    "time",
+    "timestamp",
+    "timestamptz",
+    "timetz",
+];
+/// Last words of a column name that mark it as holding dates, e.g.
+/// `created_at` or `startDate`.
+const DATE_WORDS: [&str; 6] = ["at", "on", "date", "time", "timestamp", "ts"];
+/// Words before `time` that make it a duration rather than a date, e.g.
+/// `elapsed_time`.
+const DURATION_WORDS: [&str; 10] = [
+    "cpu",
+    "elapsed",
+    "exec",
+    "execution",
+    "idle",
+    "response",
+    "run",
+    "spent",
+    "total",
+    "wait",
+];
+
+const SECONDS_PER_DAY: i64 = 86_400;
+/// Julian day number of 1970-01-01 00:00 UTC
+const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+/// Values below this are too small to be a plausible unix timestamp
+/// (1973-03-03), so ids and counters in a date column are left alone.
+const MIN_UNIX_SECONDS: i64 = 100_000_000;
+/// Values from here on are taken to be milliseconds (5138-11-16 in seconds).
+const MIN_UNIX_MILLIS: i64 = 100_000_000_000;
+/// Julian days covering the years 0 to 9999.
+const JULIAN_DAYS: std::ops::Range<f64> = 1_721_058.5..5_373_484.5;
+
+/// How the values of a column are shown in the table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Formatter {
+    /// Unix seconds or milliseconds, Julian days and ISO text as
+    /// `YYYY-MM-DD HH:MM:SS` in UTC
+    Date,
+}
+
+impl Formatter {
+    /// Pick a formatter from the declared type and name of a column.
+    pub fn detect(name: &str, decl_type: &str) -> Option<Formatter> {
+        let date_type = words(decl_type)
+            .iter()
+            .any(|w| DATE_TYPES.contains(&w.as_str()));
+        if date_type || is_date_name(name) {
+            Some(Formatter::Date)
+        } else {
+            None
+        }
+    }
+
+    /// The formatted value, or None if the value doesn't fit the format
+    /// and should be shown as it is.
+    pub fn format(&self, value: &Value) -> Option<String> {
+        match self {
+            Formatter::Date => format_date(value),
+        }
+    }
+}
+
+/// True if the last word of a column name marks it as holding dates.
+fn is_date_name(name: &str) -> bool {
+    match words(name).as_slice() {
+        [.., before, last] if last == "time" => !DURATION_WORDS.contains(&before.as_str()),
+        [.., last] => DATE_WORDS.contains(&last.as_str()),
+        [] => false,
+    }
+}
+
+/// The lowercase words of a name or type, split at anything but letters
+/// and digits, and where a lowercase letter is followed by an uppercase one.
+fn words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut previous = ' ';
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            previous = c;
+            continue;
+        }
+        let starts_word =
+            !previous.is_alphanumeric() || (previous.is_lowercase() && c.is_uppercase());
+        match words.last_mut() {
+            Some(word) if !starts_word => word.extend(c.to_lowercase()),
+            _ => words.push(c.to_lowercase().collect()),
+        }
+        previous = c;
+    }
+    words
+}
+
+fn format_date(value: &Value) -> Option<String> {
+    match value {
+        Value::Integer(v) if *v >= MIN_UNIX_MILLIS => {
+            Some(format_unix(v.div_euclid(1000), Some(v.rem_euclid(1000))))
+        }
+        Value::Integer(v) if *v >= MIN_UNIX_SECONDS => Some(format_unix(*v, None)),
+        Value::Real(v) if JULIAN_DAYS.contains(v) => {
+            let millis = ((v - UNIX_EPOCH_JULIAN_DAY) * SECONDS_PER_DAY as f64 * 1000.0).round();
+            let millis = millis as i64;
+            let fraction = millis.rem_euclid(1000);
+            Some(format_unix(
+                millis.div_euclid(1000),
+                (fraction != 0).then_some(fraction),
+            ))
+        }
+        Value::Real(v) if *v >= MIN_UNIX_SECONDS as f64 && *v < MIN_UNIX_MILLIS as f64 => {
+            let millis = (v * 1000.0).round() as i64;
+            Some(format_unix(
+                millis.div_euclid(1000),
+                Some(millis.rem_euclid(1000)),
+            ))
+        }
+        Value::Text(bytes) => format_iso(std::str::from_utf8(bytes).ok()?),
+        _ => None,
+    }
+}
+
+/// `YYYY-MM-DD HH:MM:SS[.mmm]` for a unix timestamp.
+fn format_unix(seconds: i64, millis: Option<i64>) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+    let time = seconds.rem_euclid(SECONDS_PER_DAY);
+    let mut out = format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    );
+    if let Some(millis) = millis {
+        out.push_str(&format!(".{millis:03}"));
+    }
+    out
+}
+
+/// Tidy ISO 8601 text like `2024-01-31T12:00:00Z` into the same shape as
+/// the other formats, keeping any fraction and time zone offset.
+fn format_iso(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let is_date = bytes.len() >= 10
+        && bytes[..10].iter().enumerate().all(|(i, b)| {
+            matches!(i, 4 | 7) && *b == b'-' || !matches!(i, 4 | 7) && b.is_ascii_digit()
+        });
+    if !is_date {
+        return None;
+    }
+    let rest = &text[10..];
+    if rest.is_empty() {
+        return Some(text.to_string());
+    }
+    let time = rest.strip_prefix(['T', ' '])?;
+    let time = time.strip_suffix('Z').unwrap_or(time);
+    Some(format!("{} {time}", &text[..10]))
+}
+
+/// Year, month and day of a number of days since 1970-01-01, see
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> Value {
+        Value::Text(text.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn formats_dates() {
+        let date = |v: Value| format_date(&v);
+        assert_eq!(
+            date(Value::Integer(1_700_000_000)).as_deref(),
+            Some("2023-11-14 22:13:20")
+        );
+        assert_eq!(
+            date(Value::Integer(1_700_000_000_123)).as_deref(),
+            Some("2023-11-14 22:13:20.123")
+        );
+        assert_eq!(
+            date(Value::Real(2_460_000.5)).as_deref(),
+            Some("2023-02-25 00:00:00")
+        );
+        assert_eq!(
+            date(Value::Real(1_700_000_000.5)).as_deref(),
+            Some("2023-11-14 22:13:20.500")
+        );
+        assert_eq!(
+            date(text("2024-01-31T12:00:00Z")).as_deref(),
+            Some("2024-01-31 12:00:00")
+        );
+        assert_eq!(
+            date(text("2024-01-31T12:00:00.5+02:00")).as_deref(),
+            Some("2024-01-31 12:00:00.5+02:00")
+        );
+        assert_eq!(date(text("2024-01-31")).as_deref(), Some("2024-01-31"));
+        // ids, small numbers and other text stay as they are
+        assert_eq!(date(Value::Integer(42)), None);
+        assert_eq!(date(Value::Real(1.5)), None);
+        assert_eq!(date(text("yesterday")), None);
+        assert_eq!(date(text("2024-1-31")), None);
+        assert_eq!(date(Value::Null), None);
+    }
+
+    #[test]
+    fn detects_date_names_by_last_word() {
+        for name in [
+            "created_at",
+            "startDate",
+            "UPDATED_ON",
+            "time",
+            "event-ts",
+            "start_time",
+        ] {
+            assert!(is_date_name(name), "{name}");
+        }
+        for name in [
+            "runtime",
+            "format",
+            "elapsed_time",
+            "cpuTime",
+            "date_count",
+            "statuses",
+            "",
+        ] {
+            assert!(!is_date_name(name), "{name}");
+        }
+    }
+
+    #[test]
+    fn detects_date_types_by_word() {
+        for decl_type in ["DATE", "datetime", "TIMESTAMP WITH TIME ZONE", "DateTime"] {
+            assert_eq!(Formatter::detect("x", decl_type), Some(Formatter::Date));
+        }
+        for decl_type in ["RUNTIME", "DOWNTIME_SECONDS", "INTEGER", ""] {
+            assert_eq!(Formatter::detect("x", decl_type), None);
+        }
+    }
+}
@@ -15,15 +15,17 @@ const FILE_TITLE: &str = " File Menu ";
 const FILE_MENU_KEYS: [[&str; 2]; 3] = [["Up", "k"], ["Down", "j"], ["Select", "Enter"]];
 
 const NAV_LIST_TITLE: &str = " Navigation List (Left side) ";
-const NAV_LIST_KEYS: [[&str; 2]; 3] = [
+const NAV_LIST_KEYS: [[&str; 2]; 5] = [
     ["Show Tables - Views", "q - e"],
     ["Up", "SHIFT + k"],
     ["Down", "SHIFT + j"],
+    ["Database Overview", "o"],
+    ["Schema Changes (tables, indexes)", "SHIFT + d"],
 ];
 
 const TABLE_VIEW_TITLE: &str = " Table View ";
-const TABLE_KEYS: [[&str; 2]; 10] = [
-    ["View Schema - Browse Data", "SHIFT + h - l"],
+const TABLE_KEYS: [[&str; 2]; 35] = [
+    ["Browse - Schema - Query - Pragmas Tabs", "SHIFT + h - l"],
     ["Page Up Half", "u"],
     ["Page Down Half", "d"],
     ["Move Cell Up", "k"],
@@ -33,12 +35,38 @@ const TABLE_KEYS: [[&str; 2]; 10] = [
     ["Yank Cell to Clipboard", "y"],
     ["Prev Page", "p"],
     ["Next Page", "n"],
+    ["First Page", "g"],
+    ["Last Page", "SHIFT + g"],
+    ["Go to Page", "#"],
+    ["Go to Row", ":"],
+    ["Follow Newest Rows (tail)", "SHIFT + f"],
+    ["Filter Rows (WHERE)", "/"],
+    ["Sort by Column (asc, desc, off)", "SHIFT + o"],
+    ["Index Suggested for Filter - Sort", "SHIFT + i"],
+    ["Toggle Paged - Continuous Scroll", "m"],
+    ["Narrow - Widen Column", "< - >"],
+    ["Freeze Columns up to Cell", "f"],
+    ["Hide - Reorder - Pin Columns", "c"],
+    ["View Cell (JSON tree, hex dump for blobs)", "v"],
+    ["Formatted - Raw Values", "t"],
+    ["Edit Cell - Query in $EDITOR", "SHIFT + e"],
+    ["Run Query (Query tab)", "Enter"],
+    ["Recall Older - Newer Query", "Up - Down"],
+    ["Search Query History", "CTRL + r"],
+    ["Save Query (Query tab)", "SHIFT + s"],
+    ["Run Saved Query (Queries list)", "Enter"],
+    ["Explain Query Plan", "x"],
+    ["Space Usage of Tables - Indexes", "s"],
+    ["Maintenance (checks, VACUUM, ...)", "SHIFT + m"],
+    ["Compare with Database (diff, patch)", "SHIFT + c"],
+    ["Change PRAGMA (Pragmas tab)", "Enter"],
 ];
 
 const GENERAL_TITLE: &str = " General ";
-const GENERAL_KEYS: [[&str; 2]; 2] = [
+const GENERAL_KEYS: [[&str; 2]; 3] = [
     ["Exit Application", "Escape"],
     ["Help Menu Open/Close", "?"],
+    ["Dismiss Error Popup", "Enter"],
 ];
 
 pub fn draw_help_window(frame: &mut Frame, lay: Rect) {
@@ -50,7 +78,7 @@ pub fn draw_help_window(frame: &mut Frame, lay: Rect) {
     frame.render_widget(background, lay);
 
     let area = center(lay, Constraint::Length(60), Constraint::Length(60));
-    let split_area = Layout::vertical(Constraint::from_lengths([5, 5, 12, 4]))
+    let split_area = Layout::vertical(Constraint::from_lengths([5, 7, 37, 5]))
         .margin(2)
         .split(area);
     let widths = Constraint::from_lengths([40, 14]);
@@ -0,0 +1,222 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+use super::{
+    formats::Formatter, input::TextInput, utils::center, HIGHLIGHT_STYLE, NULL_COLOR,
+    PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+use crate::{history::HistoryEntry, value::Value};
+
+const KEYS: &str = " Select [Up/Down] This/All Databases [Tab] Pick [Enter] Cancel [Esc] ";
+
+/// What the user did in the history search.
+pub enum SearchAction {
+    None,
+    /// Use the sql of the picked entry
+    Pick(String),
+    Cancel,
+}
+
+/// Popup searching the query history with a fuzzy pattern.
+pub struct HistorySearch {
+    input: TextInput,
+    /// Entries run against the current database
+    db_entries: Vec<HistoryEntry>,
+    all_entries: Vec<HistoryEntry>,
+    /// Search the history of every database
+    all_dbs: bool,
+    /// Indexes of the entries matching the pattern, best first
+    matches: Vec<usize>,
+    list_state: ListState,
+}
+
+impl HistorySearch {
+    pub fn new(db_entries: Vec<HistoryEntry>, all_entries: Vec<HistoryEntry>) -> Self {
+        let mut search = Self {
+            input: TextInput::default(),
+            db_entries,
+            all_entries,
+            all_dbs: false,
+            matches: Vec::new(),
+            list_state: ListState::default(),
+        };
+        search.update_matches();
+        search
+    }
+
+    fn entries(&self) -> &[HistoryEntry] {
+        match self.all_dbs {
+            true => &self.all_entries,
+            false => &self.db_entries,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> SearchAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return SearchAction::Cancel,
+            KeyCode::Enter => {
+                let picked = self
+                    .list_state
+                    .selected()
+                    .and_then(|i| self.matches.get(i))
+                    .map(|i| self.entries()[*i].sql.clone());
+                return match picked {
+                    Some(sql) => SearchAction::Pick(sql),
+                    None => SearchAction::Cancel,
+                };
+            }
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Char('p') if ctrl => self.list_state.select_previous(),
+            KeyCode::Char('n') | KeyCode::Char('r') if ctrl => self.list_state.select_next(),
+            KeyCode::Tab => {
+                self.all_dbs = !self.all_dbs;
+                self.update_matches();
+            }
+            _ if ctrl => {}
+            _ => {
+                self.input.handle_key(key);
+                self.update_matches();
+            }
+        }
+        SearchAction::None
+    }
+
+    fn update_matches(&mut self) {
+        let pattern = self.input.value.to_lowercase();
+        let mut scored: Vec<(i64, usize)> = self
+            .entries()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| fuzzy_score(&pattern, &e.sql.to_lowercase()).map(|s| (s, i)))
+            .collect();
+        // best score first, newest first among equals
+        scored.sort_by_key(|(score, i)| (-score, *i));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.list_state
+            .select((!self.matches.is_empty()).then_some(0));
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let scope = match self.all_dbs {
+            true => "all databases",
+            false => "this database",
+        };
+        let area = center(lay, Constraint::Percentage(80), Constraint::Percentage(70));
+        let block = Block::bordered()
+            .title(
+                Line::from(format!(" History - {scope} "))
+                    .fg(SECONDARY_COLOR)
+                    .bold()
+                    .centered(),
+            )
+            .title_bottom(Line::from(KEYS).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let [input, list] = Layout::vertical([Constraint::Length(2), Constraint::Fill(1)])
+            .horizontal_margin(1)
+            .areas(block.inner(area));
+
+        let entries = self.entries();
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|i| entry_line(&entries[*i], self.all_dbs))
+            .map(ListItem::from)
+            .collect();
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        self.input.draw(frame, input);
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(HIGHLIGHT_STYLE)
+                .highlight_symbol(">"),
+            list,
+            &mut self.list_state,
+        );
+    }
+}
+
+/// When, how long and how many rows, then the sql on a single line.
+fn entry_line(entry: &HistoryEntry, with_db: bool) -> Line<'static> {
+    let when = Formatter::Date
+        .format(&Value::Integer(entry.executed_at))
+        .unwrap_or_default();
+    let rows = entry
+        .row_count
+        .map(|n| format!("{n} rows"))
+        .unwrap_or_default();
+    let sql = entry.sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut spans = vec![
+        Span::from(format!("{when}  {:>6}ms  {rows:>10}  ", entry.duration_ms)).fg(NULL_COLOR),
+        Span::from(sql).fg(TEXT_COLOR),
+    ];
+    if with_db {
+        spans.push(Span::from(format!("  {}", entry.db_path)).fg(NULL_COLOR));
+    }
+    Line::from(spans)
+}
+
+/// Score of the pattern as a subsequence of the text, higher is better, or
+/// None if it doesn't match. Runs of consecutive characters and matches at
+/// word starts score higher.
+fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let mut score = 0;
+    // byte index just past the last matched character
+    let mut next: Option<usize> = None;
+    let mut previous = ' ';
+    let mut chars = text.char_indices();
+    for p in pattern.chars().filter(|c| !c.is_whitespace()) {
+        loop {
+            let (i, c) = chars.next()?;
+            let word_start = !previous.is_alphanumeric();
+            previous = c;
+            if c == p {
+                score += match next {
+                    Some(n) if n == i => 5,
+                    _ if word_start => 3,
+                    _ => 1,
+                };
+                next = Some(i + c.len_utf8());
+                break;
+            }
+        }
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_subsequences() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "select"), None);
+        // order matters
+        assert_eq!(fuzzy_score("ts", "select"), None);
+        // whitespace in the pattern is ignored
+        assert_eq!(
+            fuzzy_score("se le", "select"),
+            fuzzy_score("sele", "select")
+        );
+    }
+
+    #[test]
+    fn prefers_runs_and_word_starts() {
+        let score = |pattern| fuzzy_score(pattern, "SELECT name FROM users").unwrap();
+        // consecutive characters beat scattered ones
+        assert!(fuzzy_score("use", "users").unwrap() > fuzzy_score("use", "u_s_e").unwrap());
+        // a word start beats the middle of a word
+        assert!(score("u") > fuzzy_score("u", "aux").unwrap());
+        assert_eq!(score("FROM"), 3 + 5 + 5 + 5);
+    }
+}
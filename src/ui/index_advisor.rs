@@ -0,0 +1,130 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use super::{
+    plan_view, utils::center, HIGHLIGHTED_COLOR, NULL_COLOR, PRIMARY_COLOR, SECONDARY_COLOR,
+    TEXT_COLOR,
+};
+use crate::{app::PlanStep, schema::SchemaChange};
+use sqlformat::{format, FormatOptions, Indent, QueryParams};
+
+const TITLE: &str = " Index Suggestion ";
+const OFFER_KEYS: &str = " Create [y] Scroll [k/j] Cancel [Esc] ";
+const DONE_KEYS: &str = " Scroll [k/j] Close [Esc] ";
+
+/// What the user did with the suggestion.
+pub enum AdvisorAction {
+    None,
+    Create(SchemaChange),
+    Close,
+}
+
+/// Popup offering an index for a filter or sort that scans its table, with
+/// the plan before and, once created, after.
+pub struct IndexAdvisor {
+    /// The query of the filter, explained before and after
+    query: String,
+    formatted: String,
+    change: SchemaChange,
+    before: Vec<PlanStep>,
+    after: Option<Vec<PlanStep>>,
+    scroll: u16,
+}
+
+impl IndexAdvisor {
+    pub fn new(query: String, change: SchemaChange, before: Vec<PlanStep>) -> Self {
+        let opts = FormatOptions {
+            indent: Indent::Spaces(4),
+            uppercase: true,
+            lines_between_queries: 1,
+        };
+        Self {
+            formatted: format(&query, &QueryParams::None, opts),
+            query,
+            change,
+            before,
+            after: None,
+            scroll: 0,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Show the plan with the index created.
+    pub fn set_after(&mut self, after: Vec<PlanStep>) {
+        self.after = Some(after);
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> AdvisorAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('n') => return AdvisorAction::Close,
+            KeyCode::Char('y') if self.after.is_none() => {
+                return AdvisorAction::Create(self.change.clone())
+            }
+            KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        AdvisorAction::None
+    }
+
+    pub fn draw(&self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(80), Constraint::Percentage(80));
+        let keys = match self.after {
+            Some(_) => DONE_KEYS,
+            None => OFFER_KEYS,
+        };
+        let block = Block::bordered()
+            .title(Line::from(TITLE).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(keys).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+
+        let heading = |text: &str| Line::from(text.to_string()).fg(SECONDARY_COLOR).bold();
+        let mut lines = vec![heading("Query")];
+        lines.extend(
+            self.formatted
+                .lines()
+                .map(|l| Line::from(l.to_string()).fg(NULL_COLOR)),
+        );
+        lines.push(Line::default());
+        lines.push(heading("Plan before"));
+        lines.extend(plan_view::plan_lines(&self.before));
+        lines.push(Line::default());
+        let sql = match &self.change {
+            SchemaChange::CreateIndex { sql, .. } => sql.as_str(),
+            _ => "",
+        };
+        match &self.after {
+            None => {
+                lines.push(heading("Suggested index"));
+                lines.push(Line::from(sql.to_string()).fg(TEXT_COLOR));
+            }
+            Some(after) => {
+                lines.push(heading("Created index"));
+                lines.push(Line::from(sql.to_string()).fg(HIGHLIGHTED_COLOR));
+                lines.push(Line::default());
+                lines.push(heading("Plan after"));
+                lines.extend(plan_view::plan_lines(after));
+            }
+        }
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            inner,
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::{
+    input::TextInput, utils::center, HIGHLIGHT_STYLE, NULL_COLOR, PRIMARY_COLOR, SECONDARY_COLOR,
+    TEXT_COLOR,
+};
+use crate::schema::{self, SchemaChange};
+
+const KEYS: &str =
+    " Select [k/j] Pick [Space] Desc [d] Unique [u] Where [w] Name [n] Create [S] Cancel [Esc] ";
+
+/// What the user did in the index designer.
+pub enum IndexAction {
+    None,
+    Create(SchemaChange),
+    Cancel,
+}
+
+/// Field of the designer edited through a text input.
+enum Field {
+    Name,
+    Condition,
+}
+
+/// Popup designing a new index of a table, picking its columns in order.
+pub struct IndexDesigner {
+    table: String,
+    columns: Vec<String>,
+    /// Indexed columns as positions in `columns`, in index order, with true
+    /// for descending order
+    chosen: Vec<(usize, bool)>,
+    list_state: ListState,
+    unique: bool,
+    /// Name given by the user, generated from the columns until then
+    name: Option<String>,
+    /// Condition of a partial index
+    condition: String,
+    editing: Option<(Field, TextInput)>,
+}
+
+impl IndexDesigner {
+    pub fn new(table: &str, columns: Vec<String>) -> Self {
+        Self {
+            table: table.to_string(),
+            columns,
+            chosen: Vec::new(),
+            list_state: ListState::default().with_selected(Some(0)),
+            unique: false,
+            name: None,
+            condition: String::new(),
+            editing: None,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> IndexAction {
+        if let Some((field, input)) = &mut self.editing {
+            match key.code {
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Enter => {
+                    let value = input.value.trim().to_string();
+                    match field {
+                        Field::Name => self.name = Some(value).filter(|v| !v.is_empty()),
+                        Field::Condition => self.condition = value,
+                    }
+                    self.editing = None;
+                }
+                _ => input.handle_key(key),
+            }
+            return IndexAction::None;
+        }
+        let selected = self.list_state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return IndexAction::Cancel,
+            KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Char('k') => self.list_state.select_previous(),
+            KeyCode::Char(' ') | KeyCode::Enter if selected < self.columns.len() => {
+                match self.chosen.iter().position(|(c, _)| *c == selected) {
+                    Some(at) => {
+                        self.chosen.remove(at);
+                    }
+                    None => self.chosen.push((selected, false)),
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some((_, descending)) = self.chosen.iter_mut().find(|(c, _)| *c == selected)
+                {
+                    *descending = !*descending;
+                }
+            }
+            KeyCode::Char('u') => self.unique = !self.unique,
+            KeyCode::Char('w') => {
+                self.editing = Some((Field::Condition, TextInput::new(&self.condition)))
+            }
+            KeyCode::Char('n') => self.editing = Some((Field::Name, TextInput::new(&self.name()))),
+            KeyCode::Char('S') if !self.chosen.is_empty() => {
+                return IndexAction::Create(SchemaChange::CreateIndex {
+                    table: self.table.clone(),
+                    sql: self.sql(),
+                });
+            }
+            _ => {}
+        }
+        IndexAction::None
+    }
+
+    fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => {
+                let columns: Vec<&str> = self
+                    .chosen
+                    .iter()
+                    .map(|(c, _)| self.columns[*c].as_str())
+                    .collect();
+                schema::index_name(&self.table, &columns)
+            }
+        }
+    }
+
+    /// The `CREATE INDEX` statement for the design.
+    fn sql(&self) -> String {
+        let columns: Vec<(String, bool)> = self
+            .chosen
+            .iter()
+            .map(|(c, descending)| (self.columns[*c].clone(), *descending))
+            .collect();
+        let condition = Some(self.condition.as_str()).filter(|c| !c.is_empty());
+        schema::create_index_sql(&self.name(), &self.table, &columns, self.unique, condition)
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(70), Constraint::Percentage(70));
+        let title = format!(" Create Index on {} ", self.table);
+        let block = Block::bordered()
+            .title(Line::from(title).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(KEYS).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let [columns_area, options_area, edit_area, sql_area] = Layout::vertical([
+            Constraint::Fill(2),
+            Constraint::Length(4),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .horizontal_margin(1)
+        .areas(block.inner(area));
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let items: Vec<ListItem> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let position = self.chosen.iter().position(|(c, _)| *c == i);
+                let mark = match position {
+                    Some(at) => format!("[{}] ", at + 1),
+                    None => "[ ] ".to_string(),
+                };
+                let descending = match position.map(|at| self.chosen[at].1) {
+                    Some(true) => " desc",
+                    _ => "",
+                };
+                ListItem::from(Line::from(vec![
+                    Span::from(mark).fg(SECONDARY_COLOR),
+                    Span::from(column.clone()).fg(TEXT_COLOR),
+                    Span::from(descending).fg(NULL_COLOR),
+                ]))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(HIGHLIGHT_STYLE)
+                .highlight_symbol(">"),
+            columns_area,
+            &mut self.list_state,
+        );
+
+        let option = |label: &str, value: String| {
+            Line::from(vec![
+                Span::from(format!("{label:<8}")).fg(SECONDARY_COLOR).bold(),
+                Span::from(value).fg(TEXT_COLOR),
+            ])
+        };
+        let unique = if self.unique { "yes" } else { "no" };
+        let condition = match self.condition.is_empty() {
+            true => "all rows".to_string(),
+            false => self.condition.clone(),
+        };
+        let options = vec![
+            Line::default(),
+            option("Name", self.name()),
+            option("Unique", unique.to_string()),
+            option("Where", condition),
+        ];
+        frame.render_widget(Paragraph::new(options), options_area);
+
+        if let Some((field, input)) = &self.editing {
+            let label = match field {
+                Field::Name => "Name: ",
+                Field::Condition => "Where: ",
+            };
+            let [label_area, input_area] =
+                Layout::horizontal([Constraint::Length(label.len() as u16), Constraint::Fill(1)])
+                    .areas(edit_area);
+            frame.render_widget(Line::from(label).fg(SECONDARY_COLOR).bold(), label_area);
+            input.draw(frame, input_area);
+        }
+
+        let sql = match self.chosen.is_empty() {
+            true => "Pick the columns to index".to_string(),
+            false => self.sql(),
+        };
+        frame.render_widget(
+            Paragraph::new(sql)
+                .fg(NULL_COLOR)
+                .wrap(Wrap { trim: false }),
+            sql_area,
+        );
+    }
+}
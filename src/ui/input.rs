@@ -0,0 +1,87 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph},
+    Frame,
+};
+
+use super::{utils::center, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR};
+
+/// A single line text input.
+#[derive(Debug, Default, Clone)]
+pub struct TextInput {
+    pub value: String,
+    /// Cursor position in chars
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(value: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            cursor: value.chars().count(),
+        }
+    }
+
+    /// Apply an editing key to the input.
+    pub fn handle_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) => {
+                let at = self.byte_index();
+                self.value.insert(at, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let at = self.byte_index();
+                self.value.remove(at);
+            }
+            KeyCode::Delete if self.cursor < self.value.chars().count() => {
+                let at = self.byte_index();
+                self.value.remove(at);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.value.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.chars().count(),
+            _ => {}
+        }
+    }
+
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor)
+            .map(|(i, _)| i)
+            .unwrap_or(self.value.len())
+    }
+
+    /// Draws the input as a single line, placing the terminal cursor.
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        let width = area.width.saturating_sub(1) as usize;
+        let skip = self.cursor.saturating_sub(width);
+        let visible: String = self.value.chars().skip(skip).collect();
+        frame.render_widget(Paragraph::new(visible).fg(TEXT_COLOR), area);
+        frame.set_cursor_position((area.x + (self.cursor - skip) as u16, area.y));
+    }
+}
+
+/// Draws a centered popup asking the user for a line of text.
+pub fn draw_prompt(frame: &mut Frame, lay: Rect, title: &str, input: &TextInput) {
+    let area = center(lay, Constraint::Percentage(50), Constraint::Length(3));
+    let block = Block::bordered()
+        .title(
+            Line::from(format!(" {title} "))
+                .fg(SECONDARY_COLOR)
+                .bold()
+                .centered(),
+        )
+        .fg(PRIMARY_COLOR)
+        .border_type(BorderType::Rounded);
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    input.draw(frame, inner);
+}
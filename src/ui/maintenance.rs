@@ -0,0 +1,285 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::{
+    fs,
+    sync::mpsc::{Receiver, TryRecvError},
+    time::Instant,
+};
+
+use super::{
+    utils::{center, format_size},
+    HIGHLIGHTED_COLOR, HIGHLIGHT_STYLE, NULL_COLOR, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+use crate::{
+    app::{App, Maintenance, MaintenanceReport},
+    error::AppError,
+};
+
+const TITLE: &str = " Maintenance ";
+const MENU_KEYS: &str = " Select [k/j] Run [Enter] Close [Esc] ";
+const RUNNING_KEYS: &str = " Running, please wait ";
+const REPORT_KEYS: &str = " Scroll [k/j] Back [Esc] ";
+/// Problems found by a check
+const PROBLEM_COLOR: Color = Color::Red;
+
+/// Name and description of each task, in the order of [`task`].
+const TASKS: [(&str, &str); 8] = [
+    ("Integrity check", "check every page, row and index"),
+    (
+        "Quick check",
+        "like the integrity check, without index contents",
+    ),
+    ("Foreign key check", "rows referencing missing parents"),
+    ("VACUUM", "rebuild the file, dropping free pages"),
+    ("VACUUM INTO", "write a compacted copy to a new file"),
+    ("ANALYZE", "gather statistics for the query planner"),
+    ("REINDEX", "rebuild every index"),
+    ("Optimize", "PRAGMA optimize, analyze where it helps"),
+];
+
+/// The task of the menu entry at the given position, `VACUUM INTO` needs
+/// a path first.
+fn task(i: usize) -> Option<Maintenance> {
+    match i {
+        0 => Some(Maintenance::IntegrityCheck),
+        1 => Some(Maintenance::QuickCheck),
+        2 => Some(Maintenance::ForeignKeyCheck),
+        3 => Some(Maintenance::Vacuum),
+        5 => Some(Maintenance::Analyze),
+        6 => Some(Maintenance::Reindex),
+        7 => Some(Maintenance::Optimize),
+        _ => None,
+    }
+}
+
+fn name(task: &Maintenance) -> &'static str {
+    let i = match task {
+        Maintenance::IntegrityCheck => 0,
+        Maintenance::QuickCheck => 1,
+        Maintenance::ForeignKeyCheck => 2,
+        Maintenance::Vacuum => 3,
+        Maintenance::VacuumInto(_) => 4,
+        Maintenance::Analyze => 5,
+        Maintenance::Reindex => 6,
+        Maintenance::Optimize => 7,
+    };
+    TASKS[i].0
+}
+
+/// What the user did in the maintenance menu.
+pub enum MaintenanceAction {
+    None,
+    /// Ask where to write the copy for `VACUUM INTO`
+    AskPath,
+    Start(Maintenance),
+    Close,
+}
+
+enum State {
+    Choosing,
+    Running {
+        task: Maintenance,
+        started: Instant,
+        rx: Receiver<Result<MaintenanceReport, AppError>>,
+    },
+    Done {
+        task: Maintenance,
+        report: MaintenanceReport,
+        scroll: ListState,
+    },
+}
+
+/// Popup running checks and upkeep on the open database.
+pub struct MaintenanceMenu {
+    list_state: ListState,
+    state: State,
+}
+
+impl Default for MaintenanceMenu {
+    fn default() -> Self {
+        Self {
+            list_state: ListState::default().with_selected(Some(0)),
+            state: State::Choosing,
+        }
+    }
+}
+
+impl MaintenanceMenu {
+    pub fn handle_key(&mut self, key: &KeyEvent) -> MaintenanceAction {
+        match &mut self.state {
+            State::Choosing => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return MaintenanceAction::Close,
+                KeyCode::Char('j') => self.list_state.select_next(),
+                KeyCode::Char('k') => self.list_state.select_previous(),
+                KeyCode::Enter => {
+                    let i = self.list_state.selected().unwrap_or(0);
+                    return match task(i) {
+                        Some(task) => MaintenanceAction::Start(task),
+                        None => MaintenanceAction::AskPath,
+                    };
+                }
+                _ => {}
+            },
+            // tasks can't be stopped halfway
+            State::Running { .. } => {}
+            State::Done { scroll, .. } => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => self.state = State::Choosing,
+                KeyCode::Char('j') => scroll.select_next(),
+                KeyCode::Char('k') => scroll.select_previous(),
+                _ => {}
+            },
+        }
+        MaintenanceAction::None
+    }
+
+    /// Run a task in the background, see [`MaintenanceMenu::poll`].
+    pub fn start(&mut self, app: &App, task: Maintenance) -> Result<(), AppError> {
+        let rx = app.start_maintenance(task.clone())?;
+        self.state = State::Running {
+            task,
+            started: Instant::now(),
+            rx,
+        };
+        Ok(())
+    }
+
+    /// Check on the running task. Returns the result once it finished,
+    /// true if the task wrote to the database.
+    pub fn poll(&mut self) -> Option<Result<bool, AppError>> {
+        let State::Running { task, rx, .. } = &self.state else {
+            return None;
+        };
+        let res = match rx.try_recv() {
+            Ok(res) => res,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {
+                self.state = State::Choosing;
+                return None;
+            }
+        };
+        let task = task.clone();
+        let wrote = !task.is_check() && !matches!(task, Maintenance::VacuumInto(_));
+        match res {
+            Ok(report) => {
+                self.state = State::Done {
+                    task,
+                    report,
+                    scroll: ListState::default(),
+                };
+                Some(Ok(wrote))
+            }
+            Err(e) => {
+                self.state = State::Choosing;
+                Some(Err(e))
+            }
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(70), Constraint::Percentage(60));
+        let keys = match self.state {
+            State::Choosing => MENU_KEYS,
+            State::Running { .. } => RUNNING_KEYS,
+            State::Done { .. } => REPORT_KEYS,
+        };
+        let block = Block::bordered()
+            .title(Line::from(TITLE).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(keys).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        match &mut self.state {
+            State::Choosing => {
+                let items: Vec<ListItem> = TASKS
+                    .iter()
+                    .map(|(name, description)| {
+                        ListItem::from(Line::from(vec![
+                            Span::from(format!("{name:<20}")).fg(TEXT_COLOR).bold(),
+                            Span::from(*description).fg(NULL_COLOR),
+                        ]))
+                    })
+                    .collect();
+                frame.render_stateful_widget(
+                    List::new(items)
+                        .highlight_style(HIGHLIGHT_STYLE)
+                        .highlight_symbol(">"),
+                    inner,
+                    &mut self.list_state,
+                );
+            }
+            State::Running { task, started, .. } => {
+                let mut lines = vec![Line::from(format!(
+                    "Running {}... {}s",
+                    name(task),
+                    started.elapsed().as_secs()
+                ))
+                .fg(TEXT_COLOR)];
+                if let Maintenance::VacuumInto(target) = task {
+                    let written = fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+                    lines.push(
+                        Line::from(format!("{} written to {target}", format_size(written)))
+                            .fg(NULL_COLOR),
+                    );
+                }
+                frame.render_widget(Paragraph::new(lines), inner);
+            }
+            State::Done {
+                task,
+                report,
+                scroll,
+            } => {
+                let mut items = vec![ListItem::from(
+                    Line::from(format!("{} finished", name(task)))
+                        .fg(SECONDARY_COLOR)
+                        .bold(),
+                )];
+                items.extend(summary(task, report).into_iter().map(ListItem::from));
+                frame.render_stateful_widget(
+                    List::new(items).highlight_style(HIGHLIGHT_STYLE),
+                    inner,
+                    scroll,
+                );
+            }
+        }
+    }
+}
+
+/// Lines describing what a finished task found or did.
+fn summary(task: &Maintenance, report: &MaintenanceReport) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if let Some((before, after)) = report.sizes {
+        let label = match task {
+            Maintenance::VacuumInto(target) => format!("Copy written to {target}: "),
+            _ => "File size: ".to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::from(label).fg(TEXT_COLOR),
+            Span::from(format!("{} -> {}", format_size(before), format_size(after)))
+                .fg(HIGHLIGHTED_COLOR),
+        ]));
+    }
+    if task.is_check() {
+        match report.lines.len() {
+            0 => lines.push(Line::from("No problems found").fg(HIGHLIGHTED_COLOR)),
+            n => {
+                lines.push(Line::from(format!("{n} problems found:")).fg(PROBLEM_COLOR));
+                lines.extend(
+                    report
+                        .lines
+                        .iter()
+                        .map(|l| Line::from(l.clone()).fg(TEXT_COLOR)),
+                );
+            }
+        }
+    }
+    lines
+}
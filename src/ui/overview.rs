@@ -0,0 +1,86 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Paragraph, Row, Table},
+    Frame,
+};
+
+use super::{utils::format_size, NULL_COLOR, SECONDARY_COLOR, TEXT_COLOR};
+use crate::app::{Overview, RowCount};
+
+const HINT: &str = "Select a table with [J/K], come back here with [o]";
+
+/// Draw the overview of a database: facts from its header, then every
+/// table with its row count and size. Counts still running show as dots.
+pub fn draw(
+    frame: &mut Frame,
+    area: Rect,
+    overview: &Overview,
+    tables: &[(&str, Option<RowCount>)],
+) {
+    let facts = facts(overview);
+    let [facts_area, tables_area, hint_area] = Layout::vertical([
+        Constraint::Length(facts.len() as u16 + 1),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .margin(2)
+    .areas(area);
+
+    let lines: Vec<Line> = facts
+        .into_iter()
+        .map(|(name, value)| {
+            Line::from(vec![
+                Span::from(format!("{name:<18}")).fg(SECONDARY_COLOR).bold(),
+                Span::from(value).fg(TEXT_COLOR),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), facts_area);
+
+    let rows = tables.iter().map(|(name, count)| {
+        let count = match count {
+            Some(RowCount::Exact(n)) => n.to_string(),
+            Some(RowCount::Estimate(n)) => format!("~{n}"),
+            None => "...".to_string(),
+        };
+        let size = overview
+            .table_sizes
+            .get(*name)
+            .map(|b| format_size(*b))
+            .unwrap_or_default();
+        Row::new(vec![name.to_string(), count, size]).fg(TEXT_COLOR)
+    });
+    let widths = [
+        Constraint::Fill(1),
+        Constraint::Length(14),
+        Constraint::Length(12),
+    ];
+    let table = Table::new(rows, widths).header(
+        Row::new(vec!["Table", "Rows", "Size"])
+            .fg(SECONDARY_COLOR)
+            .bold(),
+    );
+    frame.render_widget(table, tables_area);
+    frame.render_widget(Line::from(HINT).fg(NULL_COLOR).right_aligned(), hint_area);
+}
+
+/// Name and value of every header fact shown.
+fn facts(overview: &Overview) -> Vec<(&'static str, String)> {
+    vec![
+        ("File size", format_size(overview.file_size)),
+        ("Page size", overview.page_size.to_string()),
+        ("Page count", overview.page_count.to_string()),
+        ("Freelist count", overview.freelist_count.to_string()),
+        ("Encoding", overview.encoding.clone()),
+        ("User version", overview.user_version.to_string()),
+        (
+            "Application id",
+            format!("{0} (0x{0:08x})", overview.application_id),
+        ),
+        ("Journal mode", overview.journal_mode.clone()),
+        ("Auto vacuum", overview.auto_vacuum.clone()),
+        ("SQLite version", overview.sqlite_version.clone()),
+    ]
+}
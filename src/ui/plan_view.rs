@@ -0,0 +1,171 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+    Frame,
+};
+use sqlformat::{format, FormatOptions, Indent, QueryParams};
+
+use super::{
+    utils::center, HIGHLIGHTED_COLOR, NULL_COLOR, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+use crate::app::PlanStep;
+
+const TITLE: &str = " Query Plan ";
+const KEYS: &str = " Scroll [k/j] Close [Esc] ";
+/// Steps reading every row of a table or index
+const SCAN_COLOR: Color = Color::Red;
+/// Steps sorting or grouping into a temporary b-tree
+const TEMP_COLOR: Color = Color::LightRed;
+
+/// Popup showing the plan sqlite chose for a query as a tree.
+pub struct PlanView {
+    sql: String,
+    steps: Vec<PlanStep>,
+    scroll: u16,
+}
+
+impl PlanView {
+    pub fn new(sql: &str, steps: Vec<PlanStep>) -> Self {
+        let opts = FormatOptions {
+            indent: Indent::Spaces(4),
+            uppercase: true,
+            lines_between_queries: 1,
+        };
+        Self {
+            sql: format(sql, &QueryParams::None, opts),
+            steps,
+            scroll: 0,
+        }
+    }
+
+    /// Returns false once the popup should close.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        true
+    }
+
+    pub fn draw(&self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(80), Constraint::Percentage(80));
+        let block = Block::bordered()
+            .title(Line::from(TITLE).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(KEYS).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let sql_height = (self.sql.lines().count() as u16 + 1).min(area.height / 3);
+        let [sql_area, plan_area] =
+            Layout::vertical([Constraint::Length(sql_height), Constraint::Fill(1)])
+                .horizontal_margin(1)
+                .areas(block.inner(area));
+
+        let mut lines = plan_lines(&self.steps);
+        let indexes = self.indexes();
+        lines.push(Line::default());
+        lines.push(Line::from(vec![
+            Span::from("Indexes used: ").fg(SECONDARY_COLOR),
+            match indexes.is_empty() {
+                true => Span::from("none").fg(SCAN_COLOR),
+                false => Span::from(indexes.join(", ")).fg(HIGHLIGHTED_COLOR),
+            },
+        ]));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(
+            Paragraph::new(self.sql.as_str())
+                .fg(NULL_COLOR)
+                .wrap(Wrap { trim: false }),
+            sql_area,
+        );
+        frame.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), plan_area);
+    }
+
+    /// Names of the indexes the plan uses, in order of first use.
+    fn indexes(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for step in &self.steps {
+            if let Some((_, name, _)) = split_index(&step.detail) {
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names
+    }
+}
+
+/// The steps of a plan drawn as a tree.
+pub fn plan_lines(steps: &[PlanStep]) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    tree_lines(steps, 0, "", &mut lines);
+    lines
+}
+
+/// Lines of the children of a step, drawn as a tree below it.
+fn tree_lines(steps: &[PlanStep], parent: i64, prefix: &str, lines: &mut Vec<Line<'static>>) {
+    let children: Vec<&PlanStep> = steps.iter().filter(|s| s.parent == parent).collect();
+    for (i, step) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let branch = if last { "└─ " } else { "├─ " };
+        let mut spans = vec![Span::from(format!("{prefix}{branch}")).fg(NULL_COLOR)];
+        spans.extend(detail_spans(&step.detail));
+        lines.push(Line::from(spans));
+        let prefix = format!("{prefix}{}", if last { "   " } else { "│  " });
+        tree_lines(steps, step.id, &prefix, lines);
+    }
+}
+
+/// True if the plan reads every row of a table, or sorts them into a
+/// temporary b-tree, where an index could help.
+pub fn is_slow(steps: &[PlanStep]) -> bool {
+    steps.iter().any(|step| {
+        let scan = step.detail.starts_with("SCAN ") && split_index(&step.detail).is_none();
+        scan || step.detail.contains("TEMP B-TREE FOR ORDER BY")
+    })
+}
+
+/// A plan step with full scans and temp b-trees highlighted and the index
+/// used picked out.
+fn detail_spans(detail: &str) -> Vec<Span<'static>> {
+    let color = if detail.starts_with("SCAN") {
+        SCAN_COLOR
+    } else if detail.contains("TEMP B-TREE") || detail.contains("AUTOMATIC") {
+        TEMP_COLOR
+    } else {
+        TEXT_COLOR
+    };
+    match split_index(detail) {
+        Some((before, name, after)) => vec![
+            Span::from(before.to_string()).fg(color),
+            Span::from(name.to_string()).fg(HIGHLIGHTED_COLOR).bold(),
+            Span::from(after.to_string()).fg(color),
+        ],
+        None => vec![Span::from(detail.to_string()).fg(color)],
+    }
+}
+
+/// Split a step detail around the name of the index it uses, e.g.
+/// `SEARCH t USING INDEX t_idx (a=?)`. Automatic indexes have no name and
+/// are not split.
+fn split_index(detail: &str) -> Option<(&str, &str, &str)> {
+    if let Some(at) = detail.find("USING INTEGER PRIMARY KEY") {
+        let start = at + "USING ".len();
+        let end = start + "INTEGER PRIMARY KEY".len();
+        return Some((&detail[..start], &detail[start..end], &detail[end..]));
+    }
+    let (at, using) = ["USING INDEX ", "USING COVERING INDEX "]
+        .iter()
+        .find_map(|using| detail.find(using).map(|at| (at, using)))?;
+    let start = at + using.len();
+    let end = detail[start..]
+        .find(' ')
+        .map_or(detail.len(), |i| start + i);
+    Some((&detail[..start], &detail[start..end], &detail[end..]))
+}
@@ -0,0 +1,100 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, List, ListItem, ListState, Padding, Paragraph},
+    Frame,
+};
+
+use super::{
+    HIGHLIGHTED_COLOR, HIGHLIGHT_STYLE, NULL_COLOR, NUMBER_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+use crate::{
+    app::{App, PragmaInfo, PragmaScope, PRAGMAS},
+    error::AppError,
+};
+
+/// The PRAGMAs of the open database with their current values.
+pub struct PragmaPanel {
+    /// Value of each of [`PRAGMAS`], in order
+    values: Vec<String>,
+    list_state: ListState,
+}
+
+impl Default for PragmaPanel {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+}
+
+impl PragmaPanel {
+    /// Read the current values again.
+    pub fn load(&mut self, app: &App) -> Result<(), AppError> {
+        self.values = app.pragma_values()?;
+        Ok(())
+    }
+
+    /// The selected PRAGMA and its value, if it can be changed.
+    pub fn selected_editable(&self) -> Option<(&PragmaInfo, &str)> {
+        let i = self.list_state.selected()?;
+        let info = PRAGMAS.get(i)?;
+        let value = self.values.get(i)?;
+        (info.scope != PragmaScope::ReadOnly).then_some((info, value.as_str()))
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Char('j') => self.list_state.select_next(),
+            KeyCode::Char('k') => self.list_state.select_previous(),
+            _ => {}
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, r: Rect) {
+        let [list_area, keys_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
+            .margin(2)
+            .areas(r);
+        let items: Vec<ListItem> = PRAGMAS
+            .iter()
+            .zip(&self.values)
+            .map(|(info, value)| {
+                let scope = match info.scope {
+                    PragmaScope::Connection => "connection",
+                    PragmaScope::Database => "database",
+                    PragmaScope::ReadOnly => "read only",
+                };
+                ListItem::from(Text::from(vec![
+                    Line::from(vec![
+                        Span::from(format!("{:<20}", info.name))
+                            .fg(SECONDARY_COLOR)
+                            .bold(),
+                        Span::from(format!("{value:<12}")).fg(NUMBER_COLOR),
+                        Span::from(scope).fg(NULL_COLOR),
+                    ]),
+                    Line::from(format!("  {}", info.description)).fg(TEXT_COLOR),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(HIGHLIGHT_STYLE)
+            .highlight_symbol(">")
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .padding(Padding::horizontal(1))
+                    .title(" PRAGMAs ".fg(SECONDARY_COLOR).bold().into_centered_line()),
+            );
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+        let keys = Line::from(vec![
+            "Select ".into(),
+            "[k/j]".fg(HIGHLIGHTED_COLOR),
+            "  Change ".into(),
+            "[Enter]".fg(HIGHLIGHTED_COLOR),
+        ]);
+        frame.render_widget(Paragraph::new(keys.right_aligned()), keys_area);
+    }
+}
@@ -0,0 +1,257 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::{utils::center, HIGHLIGHT_STYLE, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR};
+use crate::schema::SchemaChange;
+
+const MENU_KEYS: &str = " Select [k/j] Choose [Enter] Close [Esc] ";
+const CONFIRM_KEYS: &str = " Yes [y] No [n] ";
+
+/// A table or view selected in the navigation, what the menu acts on.
+pub struct Target {
+    pub name: String,
+    pub is_view: bool,
+    pub columns: Vec<String>,
+    /// Indexes that can be dropped, those of constraints can't
+    pub indexes: Vec<String>,
+}
+
+/// What the user did in the schema menu.
+pub enum SchemaAction {
+    None,
+    /// Open the table designer
+    CreateTable,
+    /// Ask for the new name of the table
+    RenameTable(String),
+    /// Ask for the definition of a column to add to the table
+    AddColumn(String),
+    /// Ask for the new name of a column of the table
+    RenameColumn(String, String),
+    /// Open the index designer for the table and its columns
+    CreateIndex(String, Vec<String>),
+    /// Make a confirmed change
+    Apply(SchemaChange),
+    Close,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Entry {
+    CreateTable,
+    RenameTable,
+    AddColumn,
+    RenameColumn,
+    DropColumn,
+    CreateIndex,
+    DropIndex,
+    DropTable,
+    DropView,
+}
+
+impl Entry {
+    fn label(self) -> &'static str {
+        match self {
+            Entry::CreateTable => "Create table...",
+            Entry::RenameTable => "Rename table",
+            Entry::AddColumn => "Add column",
+            Entry::RenameColumn => "Rename column",
+            Entry::DropColumn => "Drop column",
+            Entry::CreateIndex => "Create index...",
+            Entry::DropIndex => "Drop index",
+            Entry::DropTable => "Drop table",
+            Entry::DropView => "Drop view",
+        }
+    }
+}
+
+enum State {
+    Choosing,
+    /// Picking the column to rename or drop, or the index to drop
+    Pick(Entry, ListState),
+    Confirm(SchemaChange),
+}
+
+/// Popup with the changes that can be made to the schema from the
+/// navigation list.
+pub struct SchemaMenu {
+    target: Option<Target>,
+    entries: Vec<Entry>,
+    list_state: ListState,
+    state: State,
+}
+
+impl SchemaMenu {
+    pub fn new(target: Option<Target>) -> Self {
+        let entries = match &target {
+            None => vec![Entry::CreateTable],
+            Some(t) if t.is_view => vec![Entry::CreateTable, Entry::DropView],
+            Some(t) => {
+                let mut entries = vec![
+                    Entry::CreateTable,
+                    Entry::RenameTable,
+                    Entry::AddColumn,
+                    Entry::RenameColumn,
+                    Entry::DropColumn,
+                    Entry::CreateIndex,
+                ];
+                if !t.indexes.is_empty() {
+                    entries.push(Entry::DropIndex);
+                }
+                entries.push(Entry::DropTable);
+                entries
+            }
+        };
+        Self {
+            target,
+            entries,
+            list_state: ListState::default().with_selected(Some(0)),
+            state: State::Choosing,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> SchemaAction {
+        match &mut self.state {
+            State::Choosing => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return SchemaAction::Close,
+                KeyCode::Char('j') => self.list_state.select_next(),
+                KeyCode::Char('k') => self.list_state.select_previous(),
+                KeyCode::Enter => {
+                    if let Some(entry) =
+                        self.list_state.selected().and_then(|i| self.entries.get(i))
+                    {
+                        return self.choose(*entry);
+                    }
+                }
+                _ => {}
+            },
+            State::Pick(entry, state) => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.state = State::Choosing,
+                KeyCode::Char('j') => state.select_next(),
+                KeyCode::Char('k') => state.select_previous(),
+                KeyCode::Enter => {
+                    let Some(target) = &self.target else {
+                        return SchemaAction::None;
+                    };
+                    let choices = match entry {
+                        Entry::DropIndex => &target.indexes,
+                        _ => &target.columns,
+                    };
+                    let Some(choice) = state.selected().and_then(|i| choices.get(i)) else {
+                        return SchemaAction::None;
+                    };
+                    let (table, choice) = (target.name.clone(), choice.clone());
+                    match entry {
+                        Entry::DropColumn => {
+                            let change = SchemaChange::DropColumn {
+                                table,
+                                column: choice,
+                            };
+                            self.state = State::Confirm(change)
+                        }
+                        Entry::DropIndex => {
+                            let change = SchemaChange::DropIndex {
+                                table,
+                                index: choice,
+                            };
+                            self.state = State::Confirm(change)
+                        }
+                        _ => return SchemaAction::RenameColumn(table, choice),
+                    }
+                }
+                _ => {}
+            },
+            State::Confirm(change) => match key.code {
+                KeyCode::Char('y') => return SchemaAction::Apply(change.clone()),
+                KeyCode::Char('n') | KeyCode::Esc => self.state = State::Choosing,
+                _ => {}
+            },
+        }
+        SchemaAction::None
+    }
+
+    fn choose(&mut self, entry: Entry) -> SchemaAction {
+        let Some(target) = &self.target else {
+            return SchemaAction::CreateTable;
+        };
+        let name = target.name.clone();
+        match entry {
+            Entry::CreateTable => return SchemaAction::CreateTable,
+            Entry::RenameTable => return SchemaAction::RenameTable(name),
+            Entry::AddColumn => return SchemaAction::AddColumn(name),
+            Entry::CreateIndex => return SchemaAction::CreateIndex(name, target.columns.clone()),
+            Entry::RenameColumn | Entry::DropColumn | Entry::DropIndex => {
+                self.state = State::Pick(entry, ListState::default().with_selected(Some(0)))
+            }
+            Entry::DropTable => {
+                self.state = State::Confirm(SchemaChange::DropTable { table: name })
+            }
+            Entry::DropView => self.state = State::Confirm(SchemaChange::DropView { view: name }),
+        }
+        SchemaAction::None
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(50), Constraint::Percentage(50));
+        let title = match &self.target {
+            Some(t) => format!(" Schema - {} ", t.name),
+            None => " Schema ".to_string(),
+        };
+        let keys = match self.state {
+            State::Confirm(_) => CONFIRM_KEYS,
+            _ => MENU_KEYS,
+        };
+        let block = Block::bordered()
+            .title(Line::from(title).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(keys).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let list = |items: Vec<String>| {
+            List::new(items.into_iter().map(|i| ListItem::from(i).fg(TEXT_COLOR)))
+                .highlight_style(HIGHLIGHT_STYLE)
+                .highlight_symbol(">")
+        };
+        match &mut self.state {
+            State::Choosing => {
+                let items = self.entries.iter().map(|e| e.label().to_string()).collect();
+                frame.render_stateful_widget(list(items), inner, &mut self.list_state);
+            }
+            State::Pick(entry, state) => {
+                let choices = match (&self.target, entry) {
+                    (Some(t), Entry::DropIndex) => t.indexes.clone(),
+                    (Some(t), _) => t.columns.clone(),
+                    (None, _) => Vec::new(),
+                };
+                frame.render_stateful_widget(list(choices), inner, state);
+            }
+            State::Confirm(change) => {
+                let question = match change {
+                    SchemaChange::DropColumn { table, column } => {
+                        format!("Drop column {column} of {table} and all its values?")
+                    }
+                    SchemaChange::DropTable { table } => {
+                        format!("Drop table {table} and all its rows?")
+                    }
+                    SchemaChange::DropView { view } => format!("Drop view {view}?"),
+                    SchemaChange::DropIndex { table, index } => {
+                        format!("Drop index {index} of {table}?")
+                    }
+                    _ => String::new(),
+                };
+                let text = vec![
+                    Line::from(question).fg(TEXT_COLOR).bold(),
+                    Line::from("This can't be undone.").fg(TEXT_COLOR),
+                ];
+                frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }), inner);
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Row, Table, TableState},
+    Frame,
+};
+use std::cmp::Reverse;
+use strum::Display;
+
+use super::{
+    utils::{center, format_size},
+    HIGHLIGHT_STYLE, NULL_COLOR, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+use crate::app::{percent, SpaceUsage};
+
+const KEYS: &str = " Select [k/j] Sort [s] Reverse [r] Go to Table [Enter] Close [Esc] ";
+
+/// What the space usage is sorted by.
+#[derive(Debug, Clone, Copy, Display)]
+enum SortBy {
+    Size,
+    Name,
+    Fragmentation,
+    Overhead,
+}
+
+impl SortBy {
+    fn next(self) -> SortBy {
+        match self {
+            SortBy::Size => SortBy::Name,
+            SortBy::Name => SortBy::Fragmentation,
+            SortBy::Fragmentation => SortBy::Overhead,
+            SortBy::Overhead => SortBy::Size,
+        }
+    }
+}
+
+/// What the user did in the space view.
+pub enum SpaceAction {
+    None,
+    /// Show the given table
+    GoTo(String),
+    Close,
+}
+
+/// Popup listing the space used by every table and index, like
+/// `sqlite3_analyzer` does.
+pub struct SpaceView {
+    usage: Vec<SpaceUsage>,
+    sort_by: SortBy,
+    /// Sort the other way round
+    reversed: bool,
+    state: TableState,
+}
+
+impl SpaceView {
+    pub fn new(usage: Vec<SpaceUsage>) -> Self {
+        let mut view = Self {
+            usage,
+            sort_by: SortBy::Size,
+            reversed: false,
+            state: TableState::default().with_selected(0),
+        };
+        view.sort();
+        view
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> SpaceAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return SpaceAction::Close,
+            KeyCode::Enter => {
+                if let Some(usage) = self.state.selected().and_then(|i| self.usage.get(i)) {
+                    return SpaceAction::GoTo(usage.table.clone());
+                }
+            }
+            KeyCode::Char('j') => self.state.select_next(),
+            KeyCode::Char('k') => self.state.select_previous(),
+            KeyCode::Char('s') => {
+                self.sort_by = self.sort_by.next();
+                self.reversed = false;
+                self.sort();
+            }
+            KeyCode::Char('r') => {
+                self.reversed = !self.reversed;
+                self.sort();
+            }
+            _ => {}
+        }
+        SpaceAction::None
+    }
+
+    fn sort(&mut self) {
+        match self.sort_by {
+            SortBy::Size => self.usage.sort_by_key(|u| Reverse(u.bytes)),
+            SortBy::Name => self.usage.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortBy::Fragmentation => self
+                .usage
+                .sort_by(|a, b| b.fragmentation().total_cmp(&a.fragmentation())),
+            SortBy::Overhead => self
+                .usage
+                .sort_by(|a, b| b.overhead().total_cmp(&a.overhead())),
+        }
+        if self.reversed {
+            self.usage.reverse();
+        }
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(90), Constraint::Percentage(80));
+        let total: u64 = self.usage.iter().map(|u| u.bytes).sum();
+        let title = format!(
+            " Space Usage - {} - sorted by {} ",
+            format_size(total),
+            self.sort_by
+        );
+        let block = Block::bordered()
+            .title(Line::from(title).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(KEYS).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+
+        let rows = self.usage.iter().map(|u| {
+            let kind = match u.is_index {
+                true => "index",
+                false => "table",
+            };
+            Row::new(vec![
+                u.name.clone(),
+                kind.to_string(),
+                u.pages.to_string(),
+                format_size(u.bytes),
+                format!("{:.1}%", percent(u.bytes, total)),
+                format_size(u.payload),
+                format_size(u.unused),
+                format!("{:.1}%", u.overhead()),
+                format!("{:.1}%", u.fragmentation()),
+            ])
+            .fg(if u.is_index { NULL_COLOR } else { TEXT_COLOR })
+        });
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Length(11),
+            Constraint::Length(7),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Length(9),
+            Constraint::Length(9),
+        ];
+        let header = Row::new(vec![
+            "Name", "Type", "Pages", "Size", "Share", "Payload", "Unused", "Overhead", "Frag.",
+        ])
+        .fg(SECONDARY_COLOR)
+        .bold();
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(HIGHLIGHT_STYLE)
+            .highlight_symbol(">")
+            .block(block);
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
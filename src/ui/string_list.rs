@@ -19,6 +19,16 @@ impl StringList {
         self.items = items;
     }
 
+    /// Replace the items keeping the same one selected, nothing is selected
+    /// if it is gone.
+    pub fn reload_items(&mut self, items: Vec<String>) {
+        let selected = self
+            .get_selected()
+            .and_then(|name| items.iter().position(|item| item == name));
+        self.list_state.select(selected);
+        self.items = items;
+    }
+
     pub fn get_selected(&self) -> Option<&str> {
         if let Some(selected) = self.list_state.selected() {
             if let Some(path) = self.items.get(selected) {
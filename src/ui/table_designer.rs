@@ -0,0 +1,302 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Cell, Clear, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+
+use super::{
+    input::TextInput, utils::center, HIGHLIGHT_STYLE, NULL_COLOR, PRIMARY_COLOR, SECONDARY_COLOR,
+    TEXT_COLOR,
+};
+use crate::{app::quote_ident, schema::SchemaChange, snippets};
+
+const TITLE: &str = " Create Table ";
+const KEYS: &str =
+    " Move [h/j/k/l] Edit/Toggle [Enter] Add Column [a] Remove [x] Create [S] Cancel [Esc] ";
+const FIELDS: [&str; 6] = [
+    "Name",
+    "Type",
+    "Primary Key",
+    "Not Null",
+    "Unique",
+    "Default",
+];
+
+/// What the user did in the table designer.
+pub enum DesignerAction {
+    None,
+    Create(SchemaChange),
+    Cancel,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ColumnSpec {
+    name: String,
+    kind: String,
+    primary_key: bool,
+    not_null: bool,
+    unique: bool,
+    /// Literal default value, or an expression in parentheses
+    default: String,
+}
+
+impl ColumnSpec {
+    /// The text field at a position of [`FIELDS`], None for flags.
+    fn text_mut(&mut self, field: usize) -> Option<&mut String> {
+        match field {
+            0 => Some(&mut self.name),
+            1 => Some(&mut self.kind),
+            5 => Some(&mut self.default),
+            _ => None,
+        }
+    }
+
+    fn toggle(&mut self, field: usize) {
+        match field {
+            2 => self.primary_key = !self.primary_key,
+            3 => self.not_null = !self.not_null,
+            4 => self.unique = !self.unique,
+            _ => {}
+        }
+    }
+
+    fn cells(&self) -> [String; 6] {
+        let flag = |on: bool| if on { "yes" } else { "-" }.to_string();
+        [
+            self.name.clone(),
+            self.kind.clone(),
+            flag(self.primary_key),
+            flag(self.not_null),
+            flag(self.unique),
+            self.default.clone(),
+        ]
+    }
+}
+
+/// Popup designing a new table column by column.
+pub struct TableDesigner {
+    name: TextInput,
+    columns: Vec<ColumnSpec>,
+    /// 0 is the table name, then one row per column
+    row: usize,
+    /// Position in [`FIELDS`] on column rows
+    field: usize,
+    /// Input of the field being edited
+    editing: Option<TextInput>,
+}
+
+impl Default for TableDesigner {
+    fn default() -> Self {
+        Self {
+            name: TextInput::default(),
+            columns: vec![ColumnSpec {
+                name: "id".to_string(),
+                kind: "INTEGER".to_string(),
+                primary_key: true,
+                ..ColumnSpec::default()
+            }],
+            row: 0,
+            field: 0,
+            // start by naming the table
+            editing: Some(TextInput::default()),
+        }
+    }
+}
+
+impl TableDesigner {
+    pub fn handle_key(&mut self, key: &KeyEvent) -> DesignerAction {
+        if let Some(input) = &mut self.editing {
+            match key.code {
+                KeyCode::Esc => self.editing = None,
+                KeyCode::Enter => {
+                    let value = input.value.trim().to_string();
+                    self.editing = None;
+                    match self.row {
+                        0 => self.name = TextInput::new(&value),
+                        row => {
+                            let field = self.field;
+                            if let Some(text) = self.columns[row - 1].text_mut(field) {
+                                *text = value;
+                            }
+                        }
+                    }
+                }
+                _ => input.handle_key(key),
+            }
+            return DesignerAction::None;
+        }
+        match key.code {
+            KeyCode::Esc => return DesignerAction::Cancel,
+            KeyCode::Char('j') => self.row = (self.row + 1).min(self.columns.len()),
+            KeyCode::Char('k') => self.row = self.row.saturating_sub(1),
+            KeyCode::Char('l') => self.field = (self.field + 1).min(FIELDS.len() - 1),
+            KeyCode::Char('h') => self.field = self.field.saturating_sub(1),
+            KeyCode::Enter => self.edit(),
+            KeyCode::Char('a') => {
+                self.columns.push(ColumnSpec {
+                    kind: "TEXT".to_string(),
+                    ..ColumnSpec::default()
+                });
+                self.row = self.columns.len();
+                self.field = 0;
+                self.edit();
+            }
+            KeyCode::Char('x') if self.row > 0 => {
+                self.columns.remove(self.row - 1);
+                self.row = self.row.min(self.columns.len());
+            }
+            KeyCode::Char('S') if self.is_complete() => {
+                return DesignerAction::Create(SchemaChange::CreateTable {
+                    name: self.name.value.clone(),
+                    sql: self.sql(),
+                });
+            }
+            _ => {}
+        }
+        DesignerAction::None
+    }
+
+    /// Start editing the text field under the cursor, or toggle its flag.
+    fn edit(&mut self) {
+        match self.row {
+            0 => self.editing = Some(self.name.clone()),
+            row => {
+                let field = self.field;
+                let column = &mut self.columns[row - 1];
+                match column.text_mut(field) {
+                    Some(text) => self.editing = Some(TextInput::new(text)),
+                    None => column.toggle(field),
+                }
+            }
+        }
+    }
+
+    /// True once the table and all its columns are named.
+    fn is_complete(&self) -> bool {
+        !self.name.value.is_empty()
+            && !self.columns.is_empty()
+            && self.columns.iter().all(|c| !c.name.is_empty())
+    }
+
+    /// The `CREATE TABLE` statement for the design.
+    fn sql(&self) -> String {
+        let keys: Vec<String> = self
+            .columns
+            .iter()
+            .filter(|c| c.primary_key)
+            .map(|c| quote_ident(&c.name))
+            .collect();
+        let mut definitions: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| {
+                let mut def = quote_ident(&c.name);
+                if !c.kind.is_empty() {
+                    def.push_str(&format!(" {}", c.kind));
+                }
+                if c.primary_key && keys.len() == 1 {
+                    def.push_str(" PRIMARY KEY");
+                }
+                if c.not_null {
+                    def.push_str(" NOT NULL");
+                }
+                if c.unique {
+                    def.push_str(" UNIQUE");
+                }
+                if !c.default.is_empty() {
+                    let default = match c.default.starts_with('(') {
+                        true => c.default.clone(),
+                        false => snippets::literal(&c.default),
+                    };
+                    def.push_str(&format!(" DEFAULT {default}"));
+                }
+                def
+            })
+            .collect();
+        if keys.len() > 1 {
+            definitions.push(format!("PRIMARY KEY ({})", keys.join(", ")));
+        }
+        format!(
+            "CREATE TABLE {} (\n    {}\n);",
+            quote_ident(&self.name.value),
+            definitions.join(",\n    ")
+        )
+    }
+
+    pub fn draw(&self, frame: &mut Frame, lay: Rect) {
+        let area = center(lay, Constraint::Percentage(80), Constraint::Percentage(80));
+        let block = Block::bordered()
+            .title(Line::from(TITLE).fg(SECONDARY_COLOR).bold().centered())
+            .title_bottom(Line::from(KEYS).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        let [name_area, columns_area, edit_area, sql_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .horizontal_margin(1)
+        .areas(block.inner(area));
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let name_style = match self.row {
+            0 => HIGHLIGHT_STYLE,
+            _ => TEXT_COLOR.into(),
+        };
+        frame.render_widget(
+            Line::from(vec![
+                Span::from("Table name: ").fg(SECONDARY_COLOR).bold(),
+                Span::from(self.name.value.clone()).style(name_style),
+            ]),
+            name_area,
+        );
+
+        let rows = self.columns.iter().enumerate().map(|(i, column)| {
+            let cells = column.cells().into_iter().enumerate().map(|(field, text)| {
+                let selected = self.row == i + 1 && self.field == field;
+                match selected {
+                    true => Cell::from(format!(">{text}")).style(HIGHLIGHT_STYLE),
+                    false => Cell::from(format!(" {text}")).fg(TEXT_COLOR),
+                }
+            });
+            Row::new(cells)
+        });
+        let widths = [
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ];
+        let header = Row::new(FIELDS.map(|f| format!(" {f}")))
+            .fg(SECONDARY_COLOR)
+            .bold();
+        frame.render_widget(Table::new(rows, widths).header(header), columns_area);
+
+        if let Some(input) = &self.editing {
+            let field = match self.row {
+                0 => "Table name",
+                _ => FIELDS[self.field],
+            };
+            let label = format!("{field}: ");
+            let [label_area, input_area] =
+                Layout::horizontal([Constraint::Length(label.len() as u16), Constraint::Fill(1)])
+                    .areas(edit_area);
+            frame.render_widget(Line::from(label).fg(SECONDARY_COLOR).bold(), label_area);
+            input.draw(frame, input_area);
+        }
+
+        frame.render_widget(
+            Paragraph::new(self.sql())
+                .fg(NULL_COLOR)
+                .wrap(Wrap { trim: false }),
+            sql_area,
+        );
+    }
+}
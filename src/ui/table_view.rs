@@ -1,11 +1,43 @@
 use super::{
-    colors::HIGHLIGHTED_COLOR,
+    blob_viewer::{BlobAction, BlobViewer},
+    colors::{
+        BLOB_COLOR, CHANGED_COLOR, FORMATTED_COLOR, HIGHLIGHTED_COLOR, NULL_COLOR, NUMBER_COLOR,
+    },
+    column_chooser::{ChooserAction, ColumnChooser},
+    columns::{self, Columns},
+    diff_view::{DiffAction, DiffView},
+    formats::Formatter,
+    history_search::{HistorySearch, SearchAction},
+    index_advisor::{AdvisorAction, IndexAdvisor},
+    index_designer::{IndexAction, IndexDesigner},
+    input::{self, TextInput},
+    maintenance::{MaintenanceAction, MaintenanceMenu},
+    overview,
+    plan_view::{self, PlanView},
+    pragma_panel::PragmaPanel,
+    schema_menu::{SchemaAction, SchemaMenu, Target},
+    space_view::{SpaceAction, SpaceView},
     string_list::{self, StringList},
+    table_designer::{DesignerAction, TableDesigner},
+    text_viewer::{TextViewer, ViewerAction},
     SECONDARY_COLOR, TEXT_COLOR,
 };
-use crate::app::{self, App, Db};
+use crate::{
+    app::{
+        self, App, Db, Filter, Maintenance, Overview, QueryOutcome, RowCount, Seek, TableCounts,
+        Watcher,
+    },
+    config::PageSize,
+    error::AppError,
+    history::{History, HistoryEntry},
+    layouts::{ColumnLayout, Layouts},
+    output::{Format, RowWriter},
+    schema::{self, SchemaChange},
+    snippets::{self, Snippets},
+    value::Value,
+};
 use arboard::Clipboard;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
@@ -18,6 +50,14 @@ use ratatui::{
     Frame,
 };
 use sqlformat::{format, FormatOptions, Indent, QueryParams};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 use strum::{Display, EnumIter, IntoEnumIterator};
 
 #[derive(Clone, Copy, Default, Debug, Display, EnumIter)]
@@ -27,6 +67,10 @@ pub enum SelectedTableTab {
     Browse,
     #[strum(to_string = "Schema")]
     Schema,
+    #[strum(to_string = "Query")]
+    Query,
+    #[strum(to_string = "Pragmas")]
+    Pragmas,
 }
 
 impl SelectedTableTab {
@@ -37,7 +81,7 @@ impl SelectedTableTab {
                     .saturating_add(1)
                     .clamp(0, SelectedTableTab::iter().len()),
             )
-            .unwrap_or(SelectedTableTab::Schema)
+            .unwrap_or(*self)
     }
 
     pub fn previous(&self) -> SelectedTableTab {
@@ -76,6 +120,8 @@ pub enum NavigationTab {
     Tables,
     #[strum(to_string = "Views")]
     Views,
+    #[strum(to_string = "Queries")]
+    Queries,
 }
 
 impl NavigationTab {
@@ -86,7 +132,7 @@ impl NavigationTab {
                     .saturating_add(1)
                     .clamp(0, NavigationTab::iter().len()),
             )
-            .unwrap_or(NavigationTab::Tables)
+            .unwrap_or(*self)
     }
 
     pub fn previous(&self) -> NavigationTab {
@@ -118,18 +164,207 @@ impl Widget for NavigationTab {
     }
 }
 
+/// How rows beyond the ones loaded are reached.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ScrollMode {
+    /// Discrete pages changed with the page keys
+    #[default]
+    Paged,
+    /// A sliding window of rows around the cursor, extended as it nears
+    /// either edge
+    Continuous,
+}
+
+/// Space between the columns of the data table
+const COLUMN_SPACING: u16 = 2;
+/// Characters a column is widened or narrowed by
+const RESIZE_STEP: isize = 4;
+
+/// Rows from the edge of the window at which the next chunk is fetched
+const SCROLL_MARGIN: usize = 5;
+/// Largest window kept in continuous mode, in pages
+const WINDOW_PAGES: usize = 3;
+
+/// How often the database is checked for changes in watch mode
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+/// How long rows that changed on disk stay highlighted
+const CHANGED_FOR: Duration = Duration::from_secs(2);
+
+/// What a prompt opened over the table view is asking for.
+#[derive(Clone, Debug)]
+enum PromptKind {
+    GoToPage,
+    GoToRow,
+    /// Name to save the query buffer under
+    SaveQuery,
+    /// Value of a parameter of the saved query about to run
+    Parameter(String),
+    /// New value of a PRAGMA
+    Pragma(String),
+    /// New name of a table
+    RenameTable(String),
+    /// New name of a column of a table
+    RenameColumn(String, String),
+    /// Definition of a column to add to a table
+    AddColumn(String),
+    /// Path to write a vacuumed copy of the database to
+    VacuumInto,
+    /// Condition the rows of a table are filtered by
+    Filter(String),
+    /// Path of the database to compare the open one with
+    CompareWith,
+}
+
+/// Text handed to the external editor, and what to do with the result.
+pub struct EditRequest {
+    pub text: String,
+    /// Extension of the temporary file, so the editor picks a syntax
+    pub extension: &'static str,
+    target: EditTarget,
+}
+
+enum EditTarget {
+    /// The query buffer
+    Query,
+    /// A single value of a table row
+    Cell {
+        table: String,
+        column: String,
+        rowid: i64,
+        original: Value,
+    },
+}
+
+impl PromptKind {
+    fn title(&self) -> String {
+        match self {
+            PromptKind::GoToPage => "Go to page".to_string(),
+            PromptKind::GoToRow => "Go to row".to_string(),
+            PromptKind::SaveQuery => "Save query as".to_string(),
+            PromptKind::Parameter(name) => format!("Value of :{name}"),
+            PromptKind::VacuumInto => "Write vacuumed copy to".to_string(),
+            PromptKind::Pragma(name) => format!("Set PRAGMA {name} to"),
+            PromptKind::RenameTable(table) => format!("Rename table {table} to"),
+            PromptKind::RenameColumn(_, column) => format!("Rename column {column} to"),
+            PromptKind::AddColumn(table) => format!("New column of {table}: name type constraints"),
+            PromptKind::Filter(table) => format!("Show rows of {table} where"),
+            PromptKind::CompareWith => "Compare with database".to_string(),
+        }
+    }
+}
+
+/// Following the newest rows of a table as they are added, like `tail -f`.
+struct Follow {
+    table: String,
+    /// When new rows were last looked for
+    polled_at: Instant,
+    /// The cursor left the newest row, new rows wait until it returns
+    paused: bool,
+    /// Rows of a table followed by its sort column, counted in the
+    /// background to tell if any were added
+    counting: Option<Receiver<Result<usize, AppError>>>,
+}
+
+/// A saved query waiting for the values of its parameters.
+struct PendingQuery {
+    sql: String,
+    /// Parameters still to be asked for, in reverse order
+    parameters: Vec<String>,
+    values: BTreeMap<String, String>,
+}
+
 pub struct TableView {
     pub tables_list: StringList,
     pub view_list: StringList,
+    pub query_list: StringList,
+    /// Saved queries of the open database
+    snippets: Snippets,
+    pending_query: Option<PendingQuery>,
     pub selected_table_tab: SelectedTableTab,
     pub table_nav_tab: NavigationTab,
-    pub data: (Vec<String>, Vec<Vec<String>>),
+    /// Shown until a table is selected
+    overview: Option<Overview>,
+    /// Table sizes of the overview, read in the background
+    pending_sizes: Option<Receiver<HashMap<String, u64>>>,
+    /// Row counts of the tables of the overview, counted in the background
+    overview_counts: Option<TableCounts>,
+    pub data: (Vec<String>, Vec<Vec<Value>>),
+    /// Display formatter of each column in `data`
+    formatters: Vec<Option<Formatter>>,
+    /// Show formatted values rather than raw ones
+    pub format_values: bool,
     pub table_state: TableState,
-    table_scroll_height: u16,
+    pub columns: Columns,
+    /// Saved column layouts of every table
+    pub layouts: Layouts,
+    /// Layout of the table shown
+    layout: ColumnLayout,
+    /// Columns selected for the table shown, empty for all of them
+    shown_columns: Vec<String>,
+    column_chooser: Option<ColumnChooser>,
+    blob_viewer: Option<BlobViewer>,
+    text_viewer: Option<TextViewer>,
     clipboard: Option<Clipboard>,
+    page_size_setting: PageSize,
+    /// Rows per page, follows the table height when the setting is auto
     page_size: usize,
+    /// Set when the page size changed and the page needs fetching again
+    needs_reload: bool,
+    /// Absolute index of the first row on the current page
     offset: usize,
-    pub total_rows: Option<usize>,
+    /// Where the current page starts
+    seek: Seek,
+    /// Rowids of the rows on the current page
+    row_keys: Vec<i64>,
+    pub total_rows: Option<RowCount>,
+    /// Row counts already computed, keyed by their count query
+    row_counts: HashMap<String, RowCount>,
+    /// Exact counts still running in the background
+    pending_counts: Vec<(String, Receiver<Result<usize, AppError>>)>,
+    /// Show a fast estimate while the exact row count runs
+    pub estimate_counts: bool,
+    /// Reload when the database changes on disk
+    pub watch: bool,
+    watcher: Option<Watcher>,
+    /// When the database was last checked for changes
+    watched_at: Instant,
+    /// Rows of the page that changed on disk and when, highlighted for a
+    /// moment
+    changed_rows: Option<(Instant, Vec<usize>)>,
+    follow: Option<Follow>,
+    /// How often a followed table is checked for new rows
+    pub follow_interval: Duration,
+    pub scroll_mode: ScrollMode,
+    prompt: Option<(PromptKind, TextInput)>,
+    pragma_panel: PragmaPanel,
+    /// Sql edited in the Query tab
+    pub query_buffer: String,
+    /// Browse the results of the last query rather than the selected table
+    showing_query: bool,
+    /// Every statement run
+    pub history: History,
+    history_search: Option<HistorySearch>,
+    plan_view: Option<PlanView>,
+    space_view: Option<SpaceView>,
+    maintenance: Option<MaintenanceMenu>,
+    diff_view: Option<DiffView>,
+    schema_menu: Option<SchemaMenu>,
+    table_designer: Option<TableDesigner>,
+    index_designer: Option<IndexDesigner>,
+    /// Filter and sort of each table browsed, by name
+    filters: HashMap<String, Filter>,
+    /// Index for the filter of the table shown when it scans the table,
+    /// offered with `I`
+    suggested_index: Option<IndexAdvisor>,
+    index_advisor: Option<IndexAdvisor>,
+    /// Limit and start of the page shown, to explain the query behind it
+    page_query: Option<(usize, Seek)>,
+    /// Position in the history while recalling queries with Up and Down,
+    /// and the buffer as it was before recalling started
+    recall: Option<(usize, String)>,
+    /// Set when the user asked for the external editor, the main loop
+    /// takes it since it owns the terminal
+    pub edit_request: Option<EditRequest>,
 }
 
 impl Default for TableView {
@@ -137,21 +372,75 @@ impl Default for TableView {
         Self {
             tables_list: StringList::default(),
             view_list: StringList::default(),
+            query_list: StringList::default(),
+            snippets: Snippets::default(),
+            pending_query: None,
             selected_table_tab: SelectedTableTab::default(),
             table_nav_tab: NavigationTab::default(),
+            overview: None,
+            pending_sizes: None,
+            overview_counts: None,
             data: (Vec::default(), Vec::default()),
             table_state: TableState::default(),
-            table_scroll_height: 0,
+            columns: Columns::default(),
+            layouts: Layouts::default(),
+            layout: ColumnLayout::default(),
+            shown_columns: Vec::default(),
+            column_chooser: None,
+            blob_viewer: None,
+            text_viewer: None,
+            formatters: Vec::new(),
+            format_values: true,
             clipboard: Clipboard::new().ok(),
+            page_size_setting: PageSize::default(),
             page_size: 50,
+            needs_reload: false,
             offset: 0,
+            seek: Seek::Offset(0),
+            row_keys: Vec::default(),
             total_rows: None,
+            row_counts: HashMap::default(),
+            pending_counts: Vec::default(),
+            estimate_counts: false,
+            watch: false,
+            watcher: None,
+            watched_at: Instant::now(),
+            changed_rows: None,
+            follow: None,
+            follow_interval: Duration::from_secs(1),
+            scroll_mode: ScrollMode::default(),
+            prompt: None,
+            pragma_panel: PragmaPanel::default(),
+            query_buffer: String::default(),
+            showing_query: false,
+            history: History::default(),
+            history_search: None,
+            plan_view: None,
+            space_view: None,
+            maintenance: None,
+            diff_view: None,
+            schema_menu: None,
+            table_designer: None,
+            index_designer: None,
+            filters: HashMap::default(),
+            suggested_index: None,
+            index_advisor: None,
+            page_query: None,
+            recall: None,
+            edit_request: None,
         }
     }
 }
 
 impl TableView {
-    pub fn load_nav(&mut self, db: &Db) {
+    /// Load the lists of the navigation for the current database and show
+    /// its overview.
+    pub fn load_nav(&mut self, app: &App) -> Result<(), AppError> {
+        let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        self.row_counts.clear();
+        self.pending_counts.clear();
+        self.overview_counts = None;
+        self.filters.clear();
         self.tables_list.load_items(
             db.tables
                 .iter()
@@ -166,6 +455,118 @@ impl TableView {
                 .collect::<Vec<_>>()
                 .clone(),
         );
+        self.snippets = Snippets::load(&db.path)?;
+        self.query_list.load_items(self.snippets.names());
+        if matches!(self.selected_table_tab, SelectedTableTab::Pragmas) {
+            self.pragma_panel.load(app)?;
+        }
+        self.show_overview(app, db)
+    }
+
+    /// Deselect the table shown and show the overview of the database in
+    /// its place.
+    fn show_overview(&mut self, app: &App, db: &Db) -> Result<(), AppError> {
+        self.nav_list().list_state.select(None);
+        self.showing_query = false;
+        self.data = (Vec::default(), Vec::default());
+        self.row_keys.clear();
+        self.total_rows = None;
+        self.overview = Some(app.overview()?);
+        self.pending_sizes = Some(app.table_sizes_in_background()?);
+        self.count_overview(app, db, false)
+    }
+
+    /// Read the header facts of the overview again, keeping the table sizes
+    /// until they are read again.
+    fn reload_overview(&mut self, app: &App) -> Result<(), AppError> {
+        let sizes = self.overview.take().map(|o| o.table_sizes);
+        self.overview = Some(Overview {
+            table_sizes: sizes.unwrap_or_default(),
+            ..app.overview()?
+        });
+        Ok(())
+    }
+
+    /// Count the rows of the tables of the overview in the background, one
+    /// count at a time. Counts already known are shown until the new ones
+    /// arrive, and only counted again if `stale`.
+    fn count_overview(&mut self, app: &App, db: &Db, stale: bool) -> Result<(), AppError> {
+        if self.overview_counts.is_some() {
+            return Ok(());
+        }
+        let mut tables = Vec::new();
+        for table in &db.tables {
+            let key = app::count_sql(table, &Filter::default());
+            match self.row_counts.get(&key) {
+                Some(RowCount::Exact(_)) if !stale => continue,
+                Some(_) => {}
+                None if self.estimate_counts => {
+                    if let Some(estimate) = app.estimate_rows(table)? {
+                        self.row_counts.insert(key, RowCount::Estimate(estimate));
+                    }
+                }
+                None => {}
+            }
+            tables.push(table);
+        }
+        if !tables.is_empty() {
+            self.overview_counts = Some(app.count_tables_in_background(&tables)?);
+        }
+        Ok(())
+    }
+
+    /// Counts went stale after a write. Drops them, but keeps showing the
+    /// counts on screen until the new ones, counted in the background,
+    /// arrive.
+    fn recount(&mut self, app: &App, db: &Db) -> Result<(), AppError> {
+        let Some(table) = self.get_selected_table(db) else {
+            let shown: Vec<String> = db
+                .tables
+                .iter()
+                .map(|t| app::count_sql(t, &Filter::default()))
+                .collect();
+            self.row_counts.retain(|key, _| shown.contains(key));
+            self.pending_counts.clear();
+            return self.count_overview(app, db, true);
+        };
+        let filter = self.filter(table);
+        let key = app::count_sql(table, &filter);
+        self.row_counts.retain(|k, _| *k == key);
+        self.pending_counts.retain(|(k, _)| *k == key);
+        if self.row_counts.contains_key(&key) && self.pending_counts.is_empty() {
+            let rx = app.count_rows_in_background(table, &filter)?;
+            self.pending_counts.push((key, rx));
+        }
+        Ok(())
+    }
+
+    /// Read the tables again after a write, keeping the table shown. Counts
+    /// are redone in the background.
+    fn reload_tables(&mut self, app: &mut App) -> Result<(), AppError> {
+        app.reload_db()?;
+        let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        self.tables_list
+            .reload_items(db.tables.iter().map(|t| t.name.clone()).collect());
+        self.view_list
+            .reload_items(db.views.iter().map(|t| t.name.clone()).collect());
+        if self.get_selected_table(db).is_none() {
+            self.reload_overview(app)?;
+        }
+        self.recount(app, db)
+    }
+
+    /// The filter and sort the rows of a table are browsed with.
+    fn filter(&self, table: &app::Table) -> Filter {
+        self.filters.get(&table.name).cloned().unwrap_or_default()
+    }
+
+    /// The list shown by the navigation tab.
+    fn nav_list(&mut self) -> &mut StringList {
+        match self.table_nav_tab {
+            NavigationTab::Tables => &mut self.tables_list,
+            NavigationTab::Views => &mut self.view_list,
+            NavigationTab::Queries => &mut self.query_list,
+        }
     }
 
     pub fn draw(&mut self, frame: &mut Frame, db: &Db) {
@@ -184,12 +585,86 @@ impl TableView {
         self.draw_nav_lists(frame, nav_tab_inner[0]);
 
         // Table Body
-        if let Some(table) = self.get_selected_table(db) {
+        if matches!(self.selected_table_tab, SelectedTableTab::Query) {
+            self.draw_query(frame, r);
+        } else if matches!(self.selected_table_tab, SelectedTableTab::Pragmas) {
+            self.pragma_panel.draw(frame, r);
+        } else if let Some(table) = self.get_selected_table(db) {
             self.draw_body(frame, table, r);
+        } else if let Some(overview) = &self.overview {
+            let counts: Vec<(&str, Option<RowCount>)> = db
+                .tables
+                .iter()
+                .map(|t| {
+                    let key = app::count_sql(t, &Filter::default());
+                    (t.name.as_str(), self.row_counts.get(&key).copied())
+                })
+                .collect();
+            overview::draw(frame, r, overview, &counts);
+        }
+
+        if let Some(menu) = &mut self.maintenance {
+            menu.draw(frame, frame.area());
+        }
+        if let Some(view) = &mut self.diff_view {
+            view.draw(frame, frame.area());
+        }
+        if let Some(menu) = &mut self.schema_menu {
+            menu.draw(frame, frame.area());
+        }
+        if let Some(designer) = &self.table_designer {
+            designer.draw(frame, frame.area());
+        }
+        if let Some(designer) = &mut self.index_designer {
+            designer.draw(frame, frame.area());
+        }
+        if let Some((kind, input)) = &self.prompt {
+            input::draw_prompt(frame, frame.area(), &kind.title(), input);
+        }
+        if let Some(chooser) = &mut self.column_chooser {
+            chooser.draw(frame, frame.area());
+        }
+        if let Some(viewer) = &mut self.blob_viewer {
+            viewer.draw(frame, r);
+        }
+        if let Some(viewer) = &mut self.text_viewer {
+            viewer.draw(frame, frame.area());
+        }
+        if let Some(search) = &mut self.history_search {
+            search.draw(frame, frame.area());
+        }
+        if let Some(plan) = &self.plan_view {
+            plan.draw(frame, frame.area());
         }
+        if let Some(space) = &mut self.space_view {
+            space.draw(frame, frame.area());
+        }
+        if let Some(advisor) = &self.index_advisor {
+            advisor.draw(frame, frame.area());
+        }
+    }
+
+    /// True while a prompt is open and all keys should go to it.
+    pub fn is_capturing_input(&self) -> bool {
+        self.prompt.is_some()
+            || self.column_chooser.is_some()
+            || self.blob_viewer.is_some()
+            || self.text_viewer.is_some()
+            || self.history_search.is_some()
+            || self.plan_view.is_some()
+            || self.space_view.is_some()
+            || self.maintenance.is_some()
+            || self.diff_view.is_some()
+            || self.schema_menu.is_some()
+            || self.table_designer.is_some()
+            || self.index_designer.is_some()
+            || self.index_advisor.is_some()
     }
 
     fn get_selected_table<'a>(&self, db: &'a Db) -> Option<&'a app::Table> {
+        if self.showing_query {
+            return db.query.as_ref();
+        }
         match self.table_nav_tab {
             NavigationTab::Tables => {
                 if let Some(table_name) = self.tables_list.get_selected() {
@@ -207,33 +682,25 @@ impl TableView {
                 }
                 None
             }
+            // saved queries are shown once run
+            NavigationTab::Queries => None,
         }
     }
 
     fn draw_nav_lists(&mut self, frame: &mut Frame, area: Rect) {
-        match self.table_nav_tab {
-            NavigationTab::Tables => {
-                frame.render_stateful_widget(
-                    string_list::to_widget(&self.tables_list.items),
-                    area,
-                    &mut self.tables_list.list_state,
-                );
-            }
-            NavigationTab::Views => {
-                frame.render_stateful_widget(
-                    string_list::to_widget(&self.view_list.items),
-                    area,
-                    &mut self.tables_list.list_state,
-                );
-            }
-        }
+        let list = self.nav_list();
+        frame.render_stateful_widget(
+            string_list::to_widget(&list.items),
+            area,
+            &mut list.list_state,
+        );
     }
 
     fn draw_body(&mut self, frame: &mut Frame, table: &app::Table, r: Rect) {
         let margin = 2;
         match self.selected_table_tab {
             SelectedTableTab::Schema => {
-                let lay = Layout::vertical([Constraint::Fill(1)])
+                let lay = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
                     .margin(margin)
                     .split(r);
                 /*
@@ -253,14 +720,29 @@ impl TableView {
                 };
 
                 // call the formatter (no Dialect parameter)
-                let formatted = format(raw_sql, &QueryParams::None, opts);
+                let mut formatted = format(raw_sql, &QueryParams::None, opts);
+                // indexes of constraints are part of the table definition
+                for sql in table.indexes.iter().filter_map(|i| i.sql.as_ref()) {
+                    formatted.push_str("\n\n");
+                    formatted.push_str(&format(sql.trim(), &QueryParams::None, opts));
+                    formatted.push(';');
+                }
 
                 // then render `formatted` instead of `raw`
                 let p = Paragraph::new(formatted)
                     .wrap(Wrap { trim: false })
                     .fg(TEXT_COLOR);
                 frame.render_widget(p, lay[0]);
+                if !table.is_query {
+                    let keys = Line::from(vec![
+                        "Create - Drop Indexes and Columns ".into(),
+                        "[shift + d]".fg(HIGHLIGHTED_COLOR),
+                    ]);
+                    frame.render_widget(Paragraph::new(keys.right_aligned()), lay[1]);
+                }
             }
+            SelectedTableTab::Query => self.draw_query(frame, r),
+            SelectedTableTab::Pragmas => self.pragma_panel.draw(frame, r),
             SelectedTableTab::Browse => {
                 // new: table, then preview, then footer for page‑info
                 let lay = Layout::vertical([
@@ -278,36 +760,110 @@ impl TableView {
                 self.draw_preview(frame, lay[1]);
 
                 // draw the page‑info *below* the preview
-                if let Some(total) = self.total_rows {
+                if let Some(count) = self.total_rows {
+                    let total = count.value();
                     let page = (self.offset / self.page_size) + 1;
-                    let last_page = ((total + self.page_size - 1) / self.page_size).max(1);
+                    let last_page = total.div_ceil(self.page_size).max(1);
                     let start = self.offset + 1;
                     let end = (self.offset + self.page_size).min(total);
-                    let info = format!(
-                        "displaying records {start}-{end} of {total}  (page {page}/{last_page})"
-                    );
+                    let info = match (self.scroll_mode, count) {
+                        (ScrollMode::Continuous, _) => {
+                            let row = self.table_state.selected().unwrap_or(0);
+                            let row = self.offset + row.min(self.data.1.len().saturating_sub(1));
+                            let approx = match count {
+                                RowCount::Exact(_) => "",
+                                RowCount::Estimate(_) => "~",
+                            };
+                            format!("row {} of {approx}{total}", row + 1)
+                        }
+                        (_, RowCount::Exact(_)) => format!(
+                            "displaying records {start}-{end} of {total}  (page {page}/{last_page})"
+                        ),
+                        (_, RowCount::Estimate(_)) => format!(
+                            "displaying records {start}-{end} of ~{total} (approximate)  (page {page}/~{last_page})"
+                        ),
+                    };
 
                     use ratatui::layout::Alignment;
                     use ratatui::widgets::Paragraph;
 
+                    // the filter on the left, the page on the right
+                    let [filter_area, info_area] = Layout::horizontal([
+                        Constraint::Fill(1),
+                        Constraint::Length(info.chars().count() as u16),
+                    ])
+                    .areas(lay[2]);
+                    frame.render_widget(self.filter_line(table), filter_area);
+
                     // render it centered/right‑aligned in that one‑row footer
                     frame.render_widget(
                         Paragraph::new(info)
                             .alignment(Alignment::Right)
                             .wrap(ratatui::widgets::Wrap { trim: true }),
-                        lay[2],
+                        info_area,
                     );
                 }
             }
         }
     }
 
+    /// The filter and sort of the rows shown, a hint when an index could
+    /// speed them up, and whether new rows are followed.
+    fn filter_line(&self, table: &app::Table) -> Line<'static> {
+        let filter = self.filter(table);
+        let mut spans = Vec::new();
+        if let Some(condition) = filter.condition {
+            spans.push(format!("where {condition}  ").fg(SECONDARY_COLOR));
+        }
+        if let Some((column, descending)) = filter.order {
+            let direction = if descending { " desc" } else { "" };
+            spans.push(format!("order by {column}{direction}  ").fg(SECONDARY_COLOR));
+        }
+        if self.suggested_index.is_some() {
+            spans.push("scans the table, index it ".fg(TEXT_COLOR));
+            spans.push("[shift + i]".fg(HIGHLIGHTED_COLOR));
+        }
+        match &self.follow {
+            Some(follow) if follow.paused => {
+                spans.push("following, paused ".fg(TEXT_COLOR));
+                spans.push("[shift + g]".fg(HIGHLIGHTED_COLOR));
+            }
+            Some(_) => spans.push("following".fg(HIGHLIGHTED_COLOR)),
+            None => {}
+        }
+        Line::from(spans)
+    }
+
+    fn draw_query(&self, frame: &mut Frame, r: Rect) {
+        let lay = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
+            .margin(2)
+            .split(r);
+        let text = match self.query_buffer.is_empty() {
+            true => Text::from("Press SHIFT + e to write a query in $EDITOR").fg(NULL_COLOR),
+            false => Text::from(self.query_buffer.as_str()).fg(TEXT_COLOR),
+        };
+        let p = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .padding(Padding::horizontal(1))
+                .title(" Query ".fg(SECONDARY_COLOR).bold().into_centered_line()),
+        );
+        frame.render_widget(p, lay[0]);
+        let keys = Line::from(vec![
+            "Edit in $EDITOR ".into(),
+            "[E]".fg(HIGHLIGHTED_COLOR),
+            "  Run ".into(),
+            "[Enter]".fg(HIGHLIGHTED_COLOR),
+        ]);
+        frame.render_widget(Paragraph::new(keys.right_aligned()), lay[1]);
+    }
+
     fn draw_preview(&mut self, frame: &mut Frame, table_inner: Rect) {
         if let Some((x, y)) = self.table_state.selected_cell() {
             if let Some(row) = self.data.1.get(x) {
                 if let Some(val) = row.get(y) {
                     let p =
-                        Paragraph::new(val.as_str())
+                        Paragraph::new(val.display())
                             .wrap(Wrap { trim: true })
                             .fg(TEXT_COLOR)
                             .block(Block::bordered().border_type(BorderType::Rounded).title(
@@ -321,23 +877,47 @@ impl TableView {
 
     pub fn draw_table(&mut self, frame: &mut Frame, area: Rect, name: &str) {
         let (table_colums, data) = &self.data;
-        let mut widths: Vec<usize> = Vec::default();
+        let texts: Vec<Vec<String>> = data
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(c, v)| self.cell_text(c, v))
+                    .collect()
+            })
+            .collect();
+        self.columns.measure(table_colums, &texts);
+
+        // borders and padding take two columns each side
+        let available = (area.width as usize).saturating_sub(4);
+        let (selected_row, selected_col) = self.table_state.selected_cell().unwrap_or((0, 0));
+        let visible = self
+            .columns
+            .visible(selected_col, available, COLUMN_SPACING as usize);
+
+        let changed: &[usize] = match &self.changed_rows {
+            Some((at, rows)) if at.elapsed() < CHANGED_FOR => rows,
+            _ => &[],
+        };
         let rows: Vec<Row> = data
             .iter()
+            .zip(&texts)
             .enumerate()
-            .map(|(i, text)| map_to_row(&mut widths, i, text))
+            .map(|(i, (values, texts))| {
+                let changed = changed.contains(&i);
+                map_to_row(&self.columns, &visible, i, values, texts, changed)
+            })
             .collect();
-        let headers: Vec<Cell> = table_colums
+        let headers: Vec<Cell> = visible
             .iter()
-            .enumerate()
-            .map(|(i, text)| map_to_cell_calc_width(&mut widths, i, text))
+            .map(|c| Cell::from(columns::truncate(&table_colums[*c], self.columns.width(*c))))
             .collect();
-        let widths: Vec<Constraint> = widths
+        let widths: Vec<Constraint> = visible
             .iter()
-            .map(|x| Constraint::Length(*x as u16))
+            .map(|c| Constraint::Length(self.columns.width(*c) as u16))
             .collect();
         let table = Table::new(rows, widths)
-            .column_spacing(2)
+            .column_spacing(COLUMN_SPACING)
             .style(Style::new().fg(TEXT_COLOR))
             .header(Row::new(headers).underlined().bold())
             .block(
@@ -347,136 +927,1501 @@ impl TableView {
                     .title(name.fg(SECONDARY_COLOR).bold().into_centered_line()),
             )
             .cell_highlight_style(Style::new().reversed());
-        frame.render_stateful_widget(table, area, &mut self.table_state);
-        self.table_scroll_height = area.height / 2;
+
+        // the widget only knows the visible columns, map the selection onto them
+        let mut state = self.table_state.clone();
+        state.select_column(visible.iter().position(|c| *c == selected_col));
+        state.select(Some(selected_row));
+        frame.render_stateful_widget(table, area, &mut state);
+        *self.table_state.offset_mut() = state.offset();
+        self.table_state.select(state.selected());
+
+        if self.page_size_setting == PageSize::Auto {
+            // borders, padding and the header row
+            let visible_rows = (area.height as usize).saturating_sub(5).max(1);
+            if visible_rows != self.page_size {
+                self.page_size = visible_rows;
+                self.needs_reload = true;
+            }
+        }
     }
 
-    pub fn handle_input(
-        &mut self,
-        key: &KeyEvent,
-        app: &mut App,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    /// Text of a cell in the table, formatted unless raw values are shown.
+    fn cell_text(&self, col: usize, value: &Value) -> String {
+        self.formatters
+            .get(col)
+            .copied()
+            .flatten()
+            .filter(|_| self.format_values)
+            .and_then(|f| f.format(value))
+            .unwrap_or_else(|| value.display().into_owned())
+    }
+
+    pub fn set_page_size(&mut self, page_size: PageSize) {
+        self.page_size_setting = page_size;
+        if let PageSize::Rows(n) = page_size {
+            self.page_size = n;
+        }
+    }
+
+    /// Most rows kept loaded in continuous mode. Small pages still get a
+    /// window wide enough for the margins at both ends.
+    fn window_size(&self) -> usize {
+        (self.page_size * WINDOW_PAGES).max(4 * SCROLL_MARGIN)
+    }
+
+    /// Rows moved by the half page keys.
+    fn half_page(&self) -> u16 {
+        (self.page_size / 2).max(1) as u16
+    }
+
+    pub fn handle_input(&mut self, key: &KeyEvent, app: &mut App) -> Result<(), AppError> {
+        if self.prompt.is_some() {
+            return self.handle_prompt_input(key, app);
+        }
+        if self.column_chooser.is_some() {
+            return self.handle_chooser_input(key, app);
+        }
+        if self.blob_viewer.is_some() {
+            return self.handle_blob_input(key, app);
+        }
+        if self.text_viewer.is_some() {
+            return self.handle_text_viewer_input(key, app);
+        }
+        if let Some(plan) = &mut self.plan_view {
+            if !plan.handle_key(key) {
+                self.plan_view = None;
+            }
+            return Ok(());
+        }
+        if let Some(menu) = &mut self.maintenance {
+            match menu.handle_key(key) {
+                MaintenanceAction::None => {}
+                MaintenanceAction::Close => self.maintenance = None,
+                MaintenanceAction::AskPath => {
+                    let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+                    let target = Path::new(&db.path).with_extension("vacuumed.db");
+                    let input = TextInput::new(&target.display().to_string());
+                    self.prompt = Some((PromptKind::VacuumInto, input));
+                }
+                MaintenanceAction::Start(task) => menu.start(app, task)?,
+            }
+            return Ok(());
+        }
+        if let Some(view) = &mut self.diff_view {
+            match view.handle_key(key) {
+                DiffAction::None => {}
+                DiffAction::Close => self.diff_view = None,
+                DiffAction::GoTo(table) => {
+                    self.diff_view = None;
+                    return self.go_to_table(app, &table);
+                }
+                DiffAction::WritePatch(path) => {
+                    let statements = view.write_patch(&path)?;
+                    app.status = Some(format!("Wrote {statements} statements to {path}"));
+                }
+            }
+            return Ok(());
+        }
+        if let Some(advisor) = &mut self.index_advisor {
+            match advisor.handle_key(key) {
+                AdvisorAction::None => {}
+                AdvisorAction::Close => self.index_advisor = None,
+                AdvisorAction::Create(change) => return self.create_suggested_index(app, change),
+            }
+            return Ok(());
+        }
+        if let Some(designer) = &mut self.index_designer {
+            match designer.handle_key(key) {
+                IndexAction::None => {}
+                IndexAction::Cancel => self.index_designer = None,
+                IndexAction::Create(change) => return self.change_schema(app, change),
+            }
+            return Ok(());
+        }
+        if let Some(designer) = &mut self.table_designer {
+            match designer.handle_key(key) {
+                DesignerAction::None => {}
+                DesignerAction::Cancel => self.table_designer = None,
+                DesignerAction::Create(change) => return self.change_schema(app, change),
+            }
+            return Ok(());
+        }
+        if let Some(menu) = &mut self.schema_menu {
+            let prompt = match menu.handle_key(key) {
+                SchemaAction::None => None,
+                SchemaAction::Close => {
+                    self.schema_menu = None;
+                    None
+                }
+                SchemaAction::CreateTable => {
+                    self.schema_menu = None;
+                    self.table_designer = Some(TableDesigner::default());
+                    None
+                }
+                SchemaAction::CreateIndex(table, columns) => {
+                    self.schema_menu = None;
+                    self.index_designer = Some(IndexDesigner::new(&table, columns));
+                    None
+                }
+                SchemaAction::RenameTable(table) => Some((
+                    PromptKind::RenameTable(table.clone()),
+                    TextInput::new(&table),
+                )),
+                SchemaAction::AddColumn(table) => {
+                    Some((PromptKind::AddColumn(table), TextInput::default()))
+                }
+                SchemaAction::RenameColumn(table, column) => {
+                    let input = TextInput::new(&column);
+                    Some((PromptKind::RenameColumn(table, column), input))
+                }
+                SchemaAction::Apply(change) => return self.change_schema(app, change),
+            };
+            if prompt.is_some() {
+                self.prompt = prompt;
+            }
+            return Ok(());
+        }
+        if let Some(space) = &mut self.space_view {
+            match space.handle_key(key) {
+                SpaceAction::None => {}
+                SpaceAction::Close => self.space_view = None,
+                SpaceAction::GoTo(table) => {
+                    self.space_view = None;
+                    return self.go_to_table(app, &table);
+                }
+            }
+            return Ok(());
+        }
+        if let Some(search) = &mut self.history_search {
+            match search.handle_key(key) {
+                SearchAction::None => {}
+                SearchAction::Cancel => self.history_search = None,
+                SearchAction::Pick(sql) => {
+                    self.history_search = None;
+                    self.recall = None;
+                    self.query_buffer = sql;
+                }
+            }
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('D') {
+            if app.read_only {
+                app.status =
+                    Some("Schema changes are disabled, the database is read-only".to_string());
+                return Ok(());
+            }
+            let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+            let target = match self.get_selected_table(db) {
+                Some(table) if !table.is_query => Some(Target {
+                    name: table.name.clone(),
+                    is_view: db.views.iter().any(|v| v.name == table.name),
+                    columns: app.table_columns(table)?,
+                    indexes: table
+                        .indexes
+                        .iter()
+                        .filter(|i| i.sql.is_some())
+                        .map(|i| i.name.clone())
+                        .collect(),
+                }),
+                _ => None,
+            };
+            self.schema_menu = Some(SchemaMenu::new(target));
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('C') {
+            let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+            // start next to the open database, most comparisons are with a copy
+            let dir = Path::new(&db.path)
+                .parent()
+                .map(|d| d.join(""))
+                .unwrap_or_default();
+            let input = TextInput::new(&dir.display().to_string());
+            self.prompt = Some((PromptKind::CompareWith, input));
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('M') {
+            if app.read_only {
+                app.status = Some("Maintenance is disabled, the database is read-only".to_string());
+            } else {
+                self.maintenance = Some(MaintenanceMenu::default());
+            }
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('x') {
+            return self.explain(app);
+        }
+        if key.code == KeyCode::Char('s') {
+            self.space_view = Some(SpaceView::new(app.space_usage()?));
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('I') {
+            match self.suggested_index.take() {
+                Some(advisor) => self.index_advisor = Some(advisor),
+                None if app.read_only => {
+                    app.status =
+                        Some("Schema changes are disabled, the database is read-only".to_string())
+                }
+                None => app.status = Some("No index to suggest for this filter".to_string()),
+            }
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('y') {
+            return self.yank_cell(app);
+        }
+        if key.code == KeyCode::Char('v') {
+            return self.open_cell_viewer(app);
+        }
+        if key.code == KeyCode::Char('t') {
+            self.format_values = !self.format_values;
+            let shown = if self.format_values {
+                "formatted"
+            } else {
+                "raw"
+            };
+            app.status = Some(format!("Showing {shown} values"));
+            return Ok(());
+        }
+        if matches!(self.selected_table_tab, SelectedTableTab::Query) {
+            match key.code {
+                KeyCode::Enter => return self.run_query(app),
+                KeyCode::Char('S') if !self.query_buffer.trim().is_empty() => {
+                    self.prompt = Some((PromptKind::SaveQuery, TextInput::default()));
+                    return Ok(());
+                }
+                KeyCode::Up => return self.recall_query(app, true),
+                KeyCode::Down => return self.recall_query(app, false),
+                KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    let db_path = app.current_db.as_ref().map(|db| db.path.as_str());
+                    self.history_search = Some(HistorySearch::new(
+                        self.history.entries(db_path)?,
+                        self.history.entries(None)?,
+                    ));
+                    return Ok(());
+                }
+                KeyCode::Char('E') => {
+                    self.recall = None;
+                    self.edit_request = Some(EditRequest {
+                        text: self.query_buffer.clone(),
+                        extension: "sql",
+                        target: EditTarget::Query,
+                    });
+                    return Ok(());
+                }
+                _ => {}
+            }
+        } else if matches!(self.selected_table_tab, SelectedTableTab::Pragmas)
+            && matches!(
+                key.code,
+                KeyCode::Enter | KeyCode::Char('j') | KeyCode::Char('k')
+            )
+        {
+            if key.code != KeyCode::Enter {
+                self.pragma_panel.handle_key(key);
+            } else if let Some((info, value)) = self.pragma_panel.selected_editable() {
+                let kind = PromptKind::Pragma(info.name.to_string());
+                self.prompt = Some((kind, TextInput::new(value)));
+            }
+            return Ok(());
+        } else if key.code == KeyCode::Char('E') {
+            return self.edit_cell(app);
+        } else if key.code == KeyCode::Enter && matches!(self.table_nav_tab, NavigationTab::Queries)
+        {
+            return self.run_snippet(app);
+        }
         if let Some(db) = &app.current_db {
             if key.code == KeyCode::Char('h') {
                 self.table_state.scroll_left_by(1);
                 return Ok(());
             } else if key.code == KeyCode::Char('u') {
-                self.table_state.scroll_up_by(self.table_scroll_height);
-                return Ok(());
+                self.table_state.scroll_up_by(self.half_page());
+                return self.fill_window(app, db);
             } else if key.code == KeyCode::Char('d') {
-                self.table_state.scroll_down_by(self.table_scroll_height);
-                return Ok(());
+                self.table_state.scroll_down_by(self.half_page());
+                return self.fill_window(app, db);
             } else if key.code == KeyCode::Char('l') {
-                self.table_state.scroll_right_by(1);
+                // the widget only sees the visible columns and can't clamp
+                if self.table_state.selected_column().unwrap_or(0) + 1 < self.data.0.len() {
+                    self.table_state.scroll_right_by(1);
+                }
+                return Ok(());
+            } else if key.code == KeyCode::Char('>') {
+                let col = self.table_state.selected_column().unwrap_or(0);
+                self.columns.resize(col, RESIZE_STEP);
+                return Ok(());
+            } else if key.code == KeyCode::Char('<') {
+                let col = self.table_state.selected_column().unwrap_or(0);
+                self.columns.resize(col, -RESIZE_STEP);
+                return Ok(());
+            } else if key.code == KeyCode::Char('f') {
+                let col = self.table_state.selected_column().unwrap_or(0);
+                self.columns.toggle_freeze(col);
+                let layout = ColumnLayout {
+                    pinned: self.columns.frozen,
+                    ..self.layout.clone()
+                };
+                return self.save_layout(db, layout);
+            } else if key.code == KeyCode::Char('c') {
+                if self.showing_query {
+                    app.status = Some("Choose the columns of a query in its SELECT".to_string());
+                } else if let Some(table) = self.get_selected_table(db) {
+                    let all = app.table_columns(table)?;
+                    self.column_chooser = Some(ColumnChooser::new(&self.layout, &all));
+                }
                 return Ok(());
             } else if key.code == KeyCode::Char('k') {
                 self.table_state.scroll_up_by(1);
-                return Ok(());
+                return self.fill_window(app, db);
             } else if key.code == KeyCode::Char('j') {
                 self.table_state.scroll_down_by(1);
+                return self.fill_window(app, db);
+            } else if key.code == KeyCode::Char('m') {
+                self.scroll_mode = match self.scroll_mode {
+                    ScrollMode::Paged => ScrollMode::Continuous,
+                    ScrollMode::Continuous => ScrollMode::Paged,
+                };
+                // restart from a page boundary in either mode
+                self.go_to_row(self.offset);
+            } else if key.code == KeyCode::Char('#') {
+                self.prompt = Some((PromptKind::GoToPage, TextInput::default()));
+                return Ok(());
+            } else if key.code == KeyCode::Char(':') {
+                self.prompt = Some((PromptKind::GoToRow, TextInput::default()));
+                return Ok(());
+            } else if key.code == KeyCode::Char('/') {
+                if let Some(table) = self.get_selected_table(db) {
+                    let condition = self.filter(table).condition.unwrap_or_default();
+                    let kind = PromptKind::Filter(table.name.clone());
+                    self.prompt = Some((kind, TextInput::new(&condition)));
+                }
+                return Ok(());
+            } else if key.code == KeyCode::Char('O') {
+                let col = self.table_state.selected_column().unwrap_or(0);
+                if let (Some(table), Some(column)) =
+                    (self.get_selected_table(db), self.data.0.get(col).cloned())
+                {
+                    // ascending, then descending, then unsorted
+                    let filter = self.filters.entry(table.name.clone()).or_default();
+                    filter.order = match filter.order.take() {
+                        Some((c, false)) if c == column => Some((c, true)),
+                        Some((c, true)) if c == column => None,
+                        _ => Some((column, false)),
+                    };
+                    self.first_page();
+                    self.load_table_data(app, db)?;
+                    // stay on the column so it can be sorted again
+                    self.table_state.select_cell(Some((0, col)));
+                }
                 return Ok(());
             } else if key.code == KeyCode::Char('e') {
                 self.table_nav_tab = self.table_nav_tab.next();
+                self.showing_query = false;
+                self.first_page();
             } else if key.code == KeyCode::Char('q') {
                 self.table_nav_tab = self.table_nav_tab.previous();
-            } else if key.code == KeyCode::Char('L') {
-                self.selected_table_tab = self.selected_table_tab.next();
-            } else if key.code == KeyCode::Char('H') {
-                self.selected_table_tab = self.selected_table_tab.previous();
+                self.showing_query = false;
+                self.first_page();
+            } else if key.code == KeyCode::Char('L') || key.code == KeyCode::Char('H') {
+                self.selected_table_tab = match key.code {
+                    KeyCode::Char('L') => self.selected_table_tab.next(),
+                    _ => self.selected_table_tab.previous(),
+                };
+                if matches!(self.selected_table_tab, SelectedTableTab::Pragmas) {
+                    self.pragma_panel.load(app)?;
+                }
+            } else if key.code == KeyCode::Char('o') {
+                return self.show_overview(app, db);
             } else if key.code == KeyCode::Char('K') {
-                self.tables_list.list_state.select_previous();
+                let list = self.nav_list();
+                match list.list_state.selected() {
+                    Some(_) => list.list_state.select_previous(),
+                    // from the overview up to the last item
+                    None => list.list_state.select(list.items.len().checked_sub(1)),
+                }
+                self.showing_query = false;
+                self.first_page();
             } else if key.code == KeyCode::Char('J') {
-                self.tables_list.list_state.select_next();
-            } else if key.code == KeyCode::Char('y') {
-                self.yank_cell()?;
-                return Ok(());
+                self.nav_list().list_state.select_next();
+                self.showing_query = false;
+                self.first_page();
             } else if key.code == KeyCode::Char('n') {
-                // next page: only if there’s more data
-                if let Some(total) = self.total_rows {
-                    let next_off = self.offset + self.page_size;
-                    if next_off < total {
-                        self.offset = next_off;
-                    }
-                }
+                self.next_page();
             } else if key.code == KeyCode::Char('p') {
-                // prev page: never go below zero
-                if self.offset >= self.page_size {
-                    self.offset -= self.page_size;
-                } else {
-                    self.offset = 0;
+                self.previous_page();
+            } else if key.code == KeyCode::Char('g') {
+                self.first_page();
+            } else if key.code == KeyCode::Char('G') {
+                self.last_page();
+                self.load_table_data(app, db)?;
+                if self.follow.is_some() {
+                    // back at the newest row, following resumes
+                    self.select_last_row();
                 }
+                return Ok(());
+            } else if key.code == KeyCode::Char('F') {
+                return self.toggle_follow(app);
             }
             self.load_table_data(app, db)?;
         }
         Ok(())
     }
 
-    fn yank_cell(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        Ok(if let Some((x, y)) = self.table_state.selected_cell() {
-            if let Some(row) = self.data.1.get(x) {
-                if let Some(val) = row.get(y) {
-                    if let Some(cb) = &mut self.clipboard {
-                        if let Err(e) = cb.set_text(val) {
-                            eprintln!("Clipboard warning: {}", e);
+    fn handle_prompt_input(&mut self, key: &KeyEvent, app: &mut App) -> Result<(), AppError> {
+        let Some((_, input)) = &mut self.prompt else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.prompt = None;
+                self.pending_query = None;
+            }
+            KeyCode::Enter => {
+                let Some((kind, input)) = self.prompt.take() else {
+                    return Ok(());
+                };
+                let text = input.value.trim().to_string();
+                match kind {
+                    PromptKind::GoToPage | PromptKind::GoToRow => {
+                        let (Ok(n), Some(db)) = (text.parse::<usize>(), &app.current_db) else {
+                            return Ok(());
+                        };
+                        if matches!(kind, PromptKind::GoToPage) {
+                            self.go_to_row(n.saturating_sub(1) * self.page_size);
+                            self.load_table_data(app, db)?;
+                        } else {
+                            let row = self.go_to_row(n.saturating_sub(1));
+                            self.load_table_data(app, db)?;
+                            self.table_state.select_cell(Some((row, 0)));
+                        }
+                    }
+                    PromptKind::SaveQuery if !text.is_empty() => {
+                        self.snippets.save(&text, self.query_buffer.trim())?;
+                        self.query_list.load_items(self.snippets.names());
+                        app.status = Some(format!("Saved query {text}"));
+                    }
+                    PromptKind::SaveQuery => {}
+                    PromptKind::Parameter(name) => {
+                        if let Some(pending) = &mut self.pending_query {
+                            pending.values.insert(name, text);
+                        }
+                        return self.ask_parameter(app);
+                    }
+                    PromptKind::Pragma(name) => {
+                        let now = app.set_pragma(&name, &text)?;
+                        self.pragma_panel.load(app)?;
+                        self.reload_overview(app)?;
+                        app.status = Some(format!("PRAGMA {name} set to {now}"));
+                    }
+                    PromptKind::RenameTable(table) if !text.is_empty() => {
+                        let change = SchemaChange::RenameTable { table, to: text };
+                        return self.change_schema(app, change);
+                    }
+                    PromptKind::RenameColumn(table, column) if !text.is_empty() => {
+                        let change = SchemaChange::RenameColumn {
+                            table,
+                            column,
+                            to: text,
+                        };
+                        return self.change_schema(app, change);
+                    }
+                    PromptKind::AddColumn(table) if !text.is_empty() => {
+                        let change = SchemaChange::AddColumn {
+                            table,
+                            definition: text,
+                        };
+                        return self.change_schema(app, change);
+                    }
+                    PromptKind::RenameTable(_)
+                    | PromptKind::RenameColumn(..)
+                    | PromptKind::AddColumn(_) => {}
+                    PromptKind::Filter(table) => {
+                        let Some(db) = &app.current_db else {
+                            return Ok(());
+                        };
+                        let filter = self.filters.entry(table.clone()).or_default();
+                        let previous = filter.condition.clone();
+                        filter.condition = Some(text).filter(|t| !t.is_empty());
+                        self.first_page();
+                        if let Err(e) = self.load_table_data(app, db) {
+                            // keep browsing with the condition that worked
+                            self.filters.entry(table).or_default().condition = previous;
+                            self.load_table_data(app, db)?;
+                            return Err(e);
+                        }
+                    }
+                    PromptKind::CompareWith if !text.is_empty() => {
+                        let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+                        let selected = self.get_selected_table(db).map(|t| t.name.clone());
+                        self.diff_view = Some(DiffView::start(app, &text, selected)?);
+                    }
+                    PromptKind::CompareWith => {}
+                    PromptKind::VacuumInto => {
+                        if let (Some(menu), false) = (&mut self.maintenance, text.is_empty()) {
+                            menu.start(app, Maintenance::VacuumInto(text))?;
                         }
                     }
-                    return Ok(());
                 }
             }
-        })
+            _ => input.handle_key(key),
+        }
+        Ok(())
+    }
+
+    /// Run the saved query selected in the navigation list, asking for the
+    /// values of its parameters first.
+    fn run_snippet(&mut self, app: &mut App) -> Result<(), AppError> {
+        let Some(sql) = self
+            .query_list
+            .get_selected()
+            .and_then(|name| self.snippets.get(name))
+        else {
+            return Ok(());
+        };
+        let mut parameters = snippets::parameters(sql);
+        parameters.reverse();
+        self.pending_query = Some(PendingQuery {
+            sql: sql.to_string(),
+            parameters,
+            values: BTreeMap::new(),
+        });
+        self.ask_parameter(app)
+    }
+
+    /// Prompt for the next parameter of the pending query, or run it once
+    /// all values are known.
+    fn ask_parameter(&mut self, app: &mut App) -> Result<(), AppError> {
+        let Some(pending) = &mut self.pending_query else {
+            return Ok(());
+        };
+        if let Some(name) = pending.parameters.pop() {
+            self.prompt = Some((PromptKind::Parameter(name), TextInput::default()));
+            return Ok(());
+        }
+        self.query_buffer = snippets::bind(&pending.sql, &pending.values);
+        self.pending_query = None;
+        self.run_query(app)
+    }
+
+    /// In continuous mode fetch the next or previous chunk of rows when the
+    /// cursor is near the edge of the loaded window.
+    fn fill_window(&mut self, app: &App, db: &Db) -> Result<(), AppError> {
+        if self.scroll_mode != ScrollMode::Continuous || self.data.1.is_empty() {
+            return Ok(());
+        }
+        let Some(table) = self.get_selected_table(db) else {
+            return Ok(());
+        };
+        let filter = self.filter(table);
+        let len = self.data.1.len();
+        let total = self.total_rows.map(|c| c.value()).unwrap_or(0);
+        let (row, col) = self.table_state.selected_cell().unwrap_or((0, 0));
+        let row = row.min(len - 1);
+
+        if row + SCROLL_MARGIN >= len && self.offset + len < total {
+            let seek = match (filter.pages_by_key(table), self.row_keys.last()) {
+                (true, Some(key)) => Seek::After(*key),
+                _ => Seek::Offset(self.offset + len),
+            };
+            let page = app.select(table, &self.shown_columns, &filter, self.page_size, seek)?;
+            // positions of changed rows shift with the window
+            self.changed_rows = None;
+            self.data.1.extend(page.rows);
+            self.row_keys.extend(page.keys);
+
+            // drop rows from the front to keep the window bounded, never the
+            // row under the cursor, which a small window is close to
+            let excess = self
+                .data
+                .1
+                .len()
+                .saturating_sub(self.window_size())
+                .min(row);
+            self.data.1.drain(..excess);
+            self.row_keys.drain(..excess.min(self.row_keys.len()));
+            self.offset += excess;
+            self.table_state.select_cell(Some((row - excess, col)));
+        } else if row < SCROLL_MARGIN && self.offset > 0 {
+            let limit = self.page_size.min(self.offset);
+            let seek = match (filter.pages_by_key(table), self.row_keys.first()) {
+                (true, Some(key)) => Seek::Before(*key),
+                _ => Seek::Offset(self.offset - limit),
+            };
+            let page = app.select(table, &self.shown_columns, &filter, limit, seek)?;
+            self.changed_rows = None;
+            let added = page.rows.len();
+            self.data.1.splice(..0, page.rows);
+            self.row_keys.splice(..0, page.keys);
+            self.offset -= added;
+
+            // drop rows from the back to keep the window bounded
+            let max = self.window_size().max(row + added + 1);
+            self.data.1.truncate(max);
+            self.row_keys.truncate(max);
+            self.table_state.select_cell(Some((row + added, col)));
+        }
+        Ok(())
+    }
+
+    fn handle_chooser_input(&mut self, key: &KeyEvent, app: &App) -> Result<(), AppError> {
+        let Some(chooser) = &mut self.column_chooser else {
+            return Ok(());
+        };
+        match chooser.handle_key(key) {
+            ChooserAction::None => {}
+            ChooserAction::Cancel => self.column_chooser = None,
+            ChooserAction::Apply(layout) => {
+                self.column_chooser = None;
+                if let Some(db) = &app.current_db {
+                    let (row, _) = self.table_state.selected_cell().unwrap_or((0, 0));
+                    self.save_layout(db, layout)?;
+                    self.go_to_row(self.offset + row);
+                    self.load_table_data(app, db)?;
+                    self.table_state.select_cell(Some((row, 0)));
+                }
+            }
+        }
+        Ok(())
     }
 
-    fn load_table_data(&mut self, app: &App, db: &Db) -> Result<(), Box<dyn std::error::Error>> {
+    /// Open the selected cell in a viewer.
+    fn open_cell_viewer(&mut self, app: &mut App) -> Result<(), AppError> {
+        let Some((row, col)) = self.table_state.selected_cell() else {
+            return Ok(());
+        };
+        let column = self.data.0.get(col).map(String::as_str).unwrap_or("");
+        match self.data.1.get(row).and_then(|r| r.get(col)) {
+            Some(Value::Blob(bytes)) => self.blob_viewer = Some(BlobViewer::new(bytes.clone())),
+            Some(Value::Null) => app.status = Some("Cell is NULL".to_string()),
+            Some(value) => self.text_viewer = Some(TextViewer::new(column, &value.display())),
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn handle_text_viewer_input(&mut self, key: &KeyEvent, app: &mut App) -> Result<(), AppError> {
+        let Some(viewer) = &mut self.text_viewer else {
+            return Ok(());
+        };
+        match viewer.handle_key(key) {
+            ViewerAction::None => {}
+            ViewerAction::Close => self.text_viewer = None,
+            ViewerAction::Yank(text) => self.copy(app, text)?,
+        }
+        Ok(())
+    }
+
+    fn handle_blob_input(&mut self, key: &KeyEvent, app: &mut App) -> Result<(), AppError> {
+        let Some(viewer) = &mut self.blob_viewer else {
+            return Ok(());
+        };
+        match viewer.handle_key(key) {
+            BlobAction::None => {}
+            BlobAction::Close => self.blob_viewer = None,
+            BlobAction::Save(path) => {
+                // never overwrite an existing file
+                let mut file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&path)?;
+                file.write_all(viewer.bytes())?;
+                app.status = Some(format!("Saved {} bytes to {path}", viewer.bytes().len()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the query buffer. Results are shown in the Browse tab, other
+    /// statements are executed and the tables reloaded. Rows returned by
+    /// statements that aren't queries are shown in a popup.
+    fn run_query(&mut self, app: &mut App) -> Result<(), AppError> {
+        let sql = self.query_buffer.trim().to_string();
+        if sql.is_empty() {
+            return Ok(());
+        }
+        self.recall = None;
+        let started = Instant::now();
+        let row_count = match app.run_query(&sql)? {
+            QueryOutcome::Changed(n) => {
+                // the statement may have changed the rows shown, or created
+                // or dropped tables
+                self.refresh(app)?;
+                app.status = Some(format!("{n} rows changed"));
+                Some(n)
+            }
+            QueryOutcome::Rows => {
+                self.showing_query = true;
+                self.selected_table_tab = SelectedTableTab::Browse;
+                self.first_page();
+                if let Some(db) = &app.current_db {
+                    // the same query may return different rows this time
+                    if let Some(query) = &db.query {
+                        self.filters.remove(&query.name);
+                        self.row_counts
+                            .remove(&app::count_sql(query, &Filter::default()));
+                    }
+                    self.load_table_data(app, db)?;
+                }
+                self.total_rows.map(|c| c.value())
+            }
+            QueryOutcome::Returned(columns, rows) => {
+                // e.g. RETURNING, rows may have changed
+                self.refresh(app)?;
+                let count = rows.len();
+                let mut text = Vec::new();
+                let mut writer = RowWriter::new(&mut text, Format::Table, columns)?;
+                for row in &rows {
+                    writer.row(row)?;
+                }
+                writer.finish()?;
+                let text = String::from_utf8_lossy(&text);
+                self.text_viewer = Some(TextViewer::new("Result", &text));
+                Some(count)
+            }
+        };
+
+        if let Some(db) = &app.current_db {
+            let executed_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64);
+            self.history.record(&HistoryEntry {
+                sql,
+                db_path: db.path.clone(),
+                executed_at,
+                duration_ms: started.elapsed().as_millis() as u64,
+                row_count,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Change the schema, then reload the navigation and show the table
+    /// changed.
+    fn change_schema(&mut self, app: &mut App, change: SchemaChange) -> Result<(), AppError> {
+        let notes = app.change_schema(&change)?;
+        self.schema_menu = None;
+        self.table_designer = None;
+        self.index_designer = None;
+        // indexes don't change which rows a filter matches
+        let filters = match change {
+            SchemaChange::CreateIndex { .. } | SchemaChange::DropIndex { .. } => {
+                std::mem::take(&mut self.filters)
+            }
+            _ => HashMap::default(),
+        };
+        self.filters = filters;
+        self.reload_tables(app)?;
+        match change.table_after() {
+            Some(table) => self.go_to_table(app, table)?,
+            None => {
+                let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+                self.show_overview(app, db)?;
+            }
+        }
+        app.status = Some(match notes.is_empty() {
+            true => "Schema changed".to_string(),
+            false => format!("Schema changed, {}", notes.join(", ")),
+        });
+        Ok(())
+    }
+
+    /// Create the index the advisor suggested and show the plan of the
+    /// filter with it.
+    fn create_suggested_index(
+        &mut self,
+        app: &mut App,
+        change: SchemaChange,
+    ) -> Result<(), AppError> {
+        self.change_schema(app, change)?;
+        if let Some(advisor) = &mut self.index_advisor {
+            advisor.set_after(app.explain(advisor.query())?);
+        }
+        Ok(())
+    }
+
+    /// An index to offer when the filter and sort of a table scan all its
+    /// rows, None if they don't or the table can't be changed.
+    fn suggest_index(
+        &self,
+        app: &App,
+        db: &Db,
+        table: &app::Table,
+        filter: &Filter,
+    ) -> Result<Option<IndexAdvisor>, AppError> {
+        let is_table = db.tables.iter().any(|t| t.name == table.name);
+        if filter.is_empty() || table.is_query || !is_table || app.read_only {
+            return Ok(None);
+        }
+        let query = app::filter_sql(table, filter);
+        let before = app.explain(&query)?;
+        if !plan_view::is_slow(&before) {
+            return Ok(None);
+        }
+        let columns = app.table_columns(table)?;
+        Ok(schema::suggest_index(table, &columns, filter)
+            .map(|change| IndexAdvisor::new(query, change, before)))
+    }
+
+    /// Select a table in the navigation and browse it.
+    fn go_to_table(&mut self, app: &mut App, name: &str) -> Result<(), AppError> {
+        let Some(i) = self.tables_list.items.iter().position(|t| t == name) else {
+            app.status = Some(format!("{name} is not in the table list"));
+            return Ok(());
+        };
+        self.table_nav_tab = NavigationTab::Tables;
+        self.selected_table_tab = SelectedTableTab::Browse;
+        self.tables_list.list_state.select(Some(i));
+        self.showing_query = false;
+        self.first_page();
+        match &app.current_db {
+            Some(db) => self.load_table_data(app, db),
+            None => Ok(()),
+        }
+    }
+
+    /// Show the plan of the query buffer in the Query tab, elsewhere of the
+    /// query selecting the page shown.
+    fn explain(&mut self, app: &App) -> Result<(), AppError> {
+        let sql = if matches!(self.selected_table_tab, SelectedTableTab::Query) {
+            Some(self.query_buffer.trim().to_string()).filter(|sql| !sql.is_empty())
+        } else {
+            app.current_db
+                .as_ref()
+                .and_then(|db| self.get_selected_table(db))
+                .zip(self.page_query)
+                .map(|(table, (limit, seek))| {
+                    app::page_sql(table, &self.shown_columns, &self.filter(table), limit, seek)
+                })
+        };
+        if let Some(sql) = sql {
+            self.plan_view = Some(PlanView::new(&sql, app.explain(&sql)?));
+        }
+        Ok(())
+    }
+
+    /// Replace the query buffer with an older (or newer) query from the
+    /// history of this database.
+    fn recall_query(&mut self, app: &App, older: bool) -> Result<(), AppError> {
+        let db_path = app.current_db.as_ref().map(|db| db.path.as_str());
+        let mut queries: Vec<String> = Vec::new();
+        for entry in self.history.entries(db_path)? {
+            if !queries.contains(&entry.sql) {
+                queries.push(entry.sql);
+            }
+        }
+        self.recall = match (self.recall.take(), older) {
+            (None, true) if !queries.is_empty() => Some((0, self.query_buffer.clone())),
+            (None, _) => None,
+            (Some((pos, draft)), true) => {
+                Some(((pos + 1).min(queries.len().saturating_sub(1)), draft))
+            }
+            (Some((0, draft)), false) => {
+                // past the newest entry, back to what was being written
+                self.query_buffer = draft;
+                None
+            }
+            (Some((pos, draft)), false) => Some((pos - 1, draft)),
+        };
+        if let Some((pos, _)) = &self.recall {
+            if let Some(sql) = queries.get(*pos) {
+                self.query_buffer = sql.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Ask for the selected cell to be edited in the external editor.
+    fn edit_cell(&mut self, app: &mut App) -> Result<(), AppError> {
+        let Some(db) = &app.current_db else {
+            return Ok(());
+        };
+        let (row, col) = self.table_state.selected_cell().unwrap_or((0, 0));
+        let (Some(table), Some(column), Some(value)) = (
+            self.get_selected_table(db),
+            self.data.0.get(col),
+            self.data.1.get(row).and_then(|r| r.get(col)),
+        ) else {
+            return Ok(());
+        };
+        let rowid = self.row_keys.get(row).copied().filter(|_| table.has_rowid);
+        let request = match (rowid, value) {
+            (None, _) => Err("Only cells of tables with a rowid can be edited"),
+            (_, Value::Blob(_)) => Err("Blobs can't be edited as text"),
+            (_, Value::Text(bytes)) if std::str::from_utf8(bytes).is_err() => {
+                Err("Cell is not valid UTF-8")
+            }
+            (Some(rowid), value) => Ok(EditRequest {
+                text: match value {
+                    Value::Null => String::default(),
+                    _ => value.display().into_owned(),
+                },
+                extension: "txt",
+                target: EditTarget::Cell {
+                    table: table.name.clone(),
+                    column: column.clone(),
+                    rowid,
+                    original: value.clone(),
+                },
+            }),
+        };
+        match request {
+            Ok(request) => self.edit_request = Some(request),
+            Err(message) => app.status = Some(message.to_string()),
+        }
+        Ok(())
+    }
+
+    /// Apply text coming back from the external editor.
+    pub fn finish_edit(
+        &mut self,
+        app: &mut App,
+        request: EditRequest,
+        edited: String,
+    ) -> Result<(), AppError> {
+        let (table, column, rowid, original) = match request.target {
+            EditTarget::Query => {
+                self.query_buffer = edited.trim_end().to_string();
+                return Ok(());
+            }
+            EditTarget::Cell {
+                table,
+                column,
+                rowid,
+                original,
+            } => (table, column, rowid, original),
+        };
+        // editors add a final newline the value didn't have
+        let text = match request.text.ends_with('\n') {
+            true => edited.as_str(),
+            false => edited.strip_suffix('\n').unwrap_or(&edited),
+        };
+        if text == request.text {
+            app.status = Some("Cell unchanged".to_string());
+            return Ok(());
+        }
+        let Some(db) = &app.current_db else {
+            return Ok(());
+        };
+        let Some(table) = db.tables.iter().find(|t| t.name == table) else {
+            return Ok(());
+        };
+        app.update_cell(table, &column, rowid, &Value::from_edited(text, &original))?;
+
+        // reload the page, keeping the cursor on the edited cell
+        let cell = self.table_state.selected_cell();
+        self.load_table_data(app, db)?;
+        self.table_state.select_cell(cell);
+        app.status = Some(format!("Updated {column} of row {rowid}"));
+        Ok(())
+    }
+
+    /// Use and remember a new column layout for the table shown.
+    fn save_layout(&mut self, db: &Db, layout: ColumnLayout) -> Result<(), AppError> {
+        if let Some(table) = self.get_selected_table(db).filter(|t| !t.is_query) {
+            self.layout = layout.clone();
+            self.layouts.save(&db.path, &table.name, layout)?;
+        }
+        Ok(())
+    }
+
+    fn first_page(&mut self) {
+        self.offset = 0;
+        self.seek = Seek::Offset(0);
+    }
+
+    fn next_page(&mut self) {
+        // only if there’s more data
+        if let Some(total) = self.total_rows.map(|c| c.value()) {
+            let next_off = self.offset
+                + match self.scroll_mode {
+                    ScrollMode::Paged => self.page_size,
+                    ScrollMode::Continuous => self.data.1.len(),
+                };
+            if next_off < total {
+                self.seek = match self.row_keys.last() {
+                    Some(key) => Seek::After(*key),
+                    None => Seek::Offset(next_off),
+                };
+                self.offset = next_off;
+            }
+        }
+    }
+
+    fn previous_page(&mut self) {
+        // never go below zero
+        let prev_off = self.offset.saturating_sub(self.page_size);
+        self.seek = match self.row_keys.first() {
+            Some(key) if prev_off > 0 => Seek::Before(*key),
+            _ => Seek::Offset(prev_off),
+        };
+        self.offset = prev_off;
+    }
+
+    fn last_page(&mut self) {
+        if let Some(total) = self.total_rows.map(|c| c.value()) {
+            self.offset = total.saturating_sub(1) / self.page_size * self.page_size;
+            self.seek = Seek::Last;
+        }
+    }
+
+    /// Move to the page containing the given row, clamped to the last page.
+    /// Returns the index of the row within that page.
+    fn go_to_row(&mut self, row: usize) -> usize {
+        let total = self.total_rows.map(|c| c.value()).unwrap_or(0);
+        let row = row.min(total.saturating_sub(1));
+        self.offset = row / self.page_size * self.page_size;
+        self.seek = Seek::Offset(self.offset);
+        row - self.offset
+    }
+
+    fn yank_cell(&mut self, app: &mut App) -> Result<(), AppError> {
+        if let Some((x, y)) = self.table_state.selected_cell() {
+            if let Some(val) = self.data.1.get(x).and_then(|row| row.get(y)) {
+                return self.copy(app, val.display().into_owned());
+            }
+        }
+        Ok(())
+    }
+
+    fn copy(&mut self, app: &mut App, text: String) -> Result<(), AppError> {
+        match &mut self.clipboard {
+            Some(cb) => {
+                cb.set_text(text)?;
+                app.status = Some("Copied to clipboard".to_string());
+            }
+            None => app.status = Some("Clipboard is not available".to_string()),
+        }
+        Ok(())
+    }
+
+    fn load_table_data(&mut self, app: &App, db: &Db) -> Result<(), AppError> {
         // always reset the cursor to top-left of the page
         self.table_state.select_cell(Some((0, 0)));
+        self.changed_rows = None;
 
         if self.selected_table_tab as usize == SelectedTableTab::Browse as usize {
-            // pick the currently selected Table or View
-            let maybe_table = match self.table_nav_tab {
-                NavigationTab::Tables => db
-                    .tables
-                    .iter()
-                    .find(|t| t.name == self.tables_list.get_selected().unwrap_or("")),
-                NavigationTab::Views => db
-                    .views
-                    .iter()
-                    .find(|v| v.name == self.view_list.get_selected().unwrap_or("")),
-            };
+            // pick the currently selected Table, View or Query
+            if let Some(table) = self.get_selected_table(db) {
+                let filter = self.filter(table);
+                // fetch and remember the grand total
+                let count = self.row_count(app, table, &filter)?;
+                self.total_rows = Some(count);
+                let total = count.value();
 
-            if let Some(table) = maybe_table {
                 // load just one page of data
-                let (cols, rows) = app.select(table, self.page_size, self.offset)?;
-                self.data = (cols, rows);
+                let limit = match self.seek {
+                    Seek::Last => total.saturating_sub(self.offset).max(1),
+                    _ => self.page_size,
+                };
+                let seek = match filter.pages_by_key(table) {
+                    true => self.seek,
+                    false => Seek::Offset(self.offset),
+                };
+                self.columns.reset_for(&table.name);
+                if table.is_query {
+                    // queries pick their own columns, and layouts are kept by table
+                    self.layout = ColumnLayout::default();
+                    self.shown_columns = Vec::default();
+                } else {
+                    self.layout = self.layouts.get(&db.path, &table.name);
+                    self.columns.frozen = self.layout.pinned;
+                    self.shown_columns = match self.layout.is_default() {
+                        true => Vec::default(),
+                        false => self.layout.shown(&app.table_columns(table)?),
+                    };
+                }
+                self.page_query = Some((limit, seek));
+                let page = app.select(table, &self.shown_columns, &filter, limit, seek)?;
+                self.suggested_index = self.suggest_index(app, db, table, &filter)?;
+                let types = app.column_types(table)?;
+                self.formatters = page
+                    .columns
+                    .iter()
+                    .map(|c| Formatter::detect(c, types.get(c).map_or("", String::as_str)))
+                    .collect();
+                self.data = (page.columns, page.rows);
+                self.row_keys = page.keys;
+            }
+        }
+        Ok(())
+    }
+
+    /// Row count of a table, computed once and then cached.
+    ///
+    /// With [`TableView::estimate_counts`] set an estimate is returned right
+    /// away and the exact count is started in the background. Estimates are
+    /// of whole tables, filtered rows are always counted.
+    fn row_count(
+        &mut self,
+        app: &App,
+        table: &app::Table,
+        filter: &Filter,
+    ) -> Result<RowCount, AppError> {
+        let key = app::count_sql(table, filter);
+        if let Some(count) = self.row_counts.get(&key) {
+            return Ok(*count);
+        }
+        if self.estimate_counts && filter.condition.is_none() {
+            if let Some(estimate) = app.estimate_rows(table)? {
+                let rx = app.count_rows_in_background(table, filter)?;
+                self.pending_counts.push((key.clone(), rx));
+                self.row_counts.insert(key, RowCount::Estimate(estimate));
+                return Ok(RowCount::Estimate(estimate));
+            }
+        }
+        let count = RowCount::Exact(app.prepare_total_rows(table, filter)?);
+        self.row_counts.insert(key, count);
+        Ok(count)
+    }
+
+    /// Start or stop following the newest rows of the table shown. Rows are
+    /// followed by rowid, or by the sort column which is made ascending.
+    fn toggle_follow(&mut self, app: &mut App) -> Result<(), AppError> {
+        if self.follow.take().is_some() {
+            app.status = Some("Stopped following".to_string());
+            return Ok(());
+        }
+        let Some(db) = &app.current_db else {
+            return Ok(());
+        };
+        let Some(table) = self.get_selected_table(db) else {
+            return Ok(());
+        };
+        let mut filter = self.filter(table);
+        let by = match &mut filter.order {
+            Some((column, descending)) => {
+                // newest rows last
+                *descending = false;
+                column.clone()
+            }
+            None if table.has_rowid => "rowid".to_string(),
+            None => {
+                app.status = Some(
+                    "Sort by a column with shift + o to follow rows without a rowid".to_string(),
+                );
+                return Ok(());
+            }
+        };
+        // count again, rows were likely added since
+        let total = app.prepare_total_rows(table, &filter)?;
+        self.row_counts
+            .insert(app::count_sql(table, &filter), RowCount::Exact(total));
+        self.filters.insert(table.name.clone(), filter);
+        self.follow = Some(Follow {
+            table: table.name.clone(),
+            polled_at: Instant::now(),
+            paused: false,
+            counting: None,
+        });
+        self.selected_table_tab = SelectedTableTab::Browse;
+        self.total_rows = Some(RowCount::Exact(total));
+        self.last_page();
+        self.load_table_data(app, db)?;
+        self.select_last_row();
+        app.status = Some(format!("Following {} by {by}", table.name));
+        Ok(())
+    }
+
+    /// Append the rows added to the followed table since the last poll and
+    /// keep the cursor on the newest one. Nothing is appended while the
+    /// cursor is away from the newest row.
+    fn poll_follow(&mut self, app: &App) -> Result<(), AppError> {
+        let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        let table = self.get_selected_table(db);
+        let Some(follow) = &mut self.follow else {
+            return Ok(());
+        };
+        let Some(table) = table.filter(|t| t.name == follow.table) else {
+            // moved on to another table
+            self.follow = None;
+            return Ok(());
+        };
+        follow.polled_at = Instant::now();
+        let total = self.total_rows.map(|c| c.value()).unwrap_or(0);
+        let len = self.data.1.len();
+        let (row, col) = self.table_state.selected_cell().unwrap_or((0, 0));
+        follow.paused = self.offset + len < total || row + 1 < len;
+        if follow.paused || !matches!(self.selected_table_tab, SelectedTableTab::Browse) {
+            return Ok(());
+        }
+
+        let filter = self.filter(table);
+        let max = match self.scroll_mode {
+            ScrollMode::Paged => self.page_size,
+            ScrollMode::Continuous => self.window_size(),
+        };
+        let page = match (filter.pages_by_key(table), self.row_keys.last()) {
+            (true, Some(key)) => {
+                app.select(table, &self.shown_columns, &filter, max, Seek::After(*key))?
+            }
+            _ => {
+                // counting the rows may take a while, new ones are selected
+                // once it is done
+                let Some(follow) = &mut self.follow else {
+                    return Ok(());
+                };
+                let count = match follow.counting.as_ref().map(Receiver::try_recv) {
+                    None => {
+                        follow.counting = Some(app.count_rows_in_background(table, &filter)?);
+                        return Ok(());
+                    }
+                    Some(Err(TryRecvError::Empty)) => return Ok(()),
+                    Some(Err(TryRecvError::Disconnected)) => {
+                        follow.counting = None;
+                        return Ok(());
+                    }
+                    Some(Ok(res)) => {
+                        follow.counting = None;
+                        res?
+                    }
+                };
+                if count <= total {
+                    return Ok(());
+                }
+                let seek = Seek::Offset(total);
+                app.select(table, &self.shown_columns, &filter, count - total, seek)?
+            }
+        };
+        let added = page.rows.len();
+        if added == 0 {
+            return Ok(());
+        }
+        if self.data.0.is_empty() {
+            self.data.0 = page.columns;
+        }
+        self.data.1.extend(page.rows);
+        self.row_keys.extend(page.keys);
 
-                // now fetch and remember the grand total
-                self.total_rows = Some(app.prepare_total_rows(table)?);
+        // drop rows from the front to keep the page bounded
+        let excess = self.data.1.len().saturating_sub(max);
+        self.data.1.drain(..excess);
+        self.row_keys.drain(..excess.min(self.row_keys.len()));
+        self.offset += excess;
+
+        let total = RowCount::Exact(total + added);
+        self.total_rows = Some(total);
+        self.row_counts
+            .insert(app::count_sql(table, &filter), total);
+        let len = self.data.1.len();
+        let new_rows = (len.saturating_sub(added)..len).collect();
+        self.changed_rows = Some((Instant::now(), new_rows));
+        self.table_state.select_cell(Some((len - 1, col)));
+        Ok(())
+    }
+
+    fn select_last_row(&mut self) {
+        let col = self.table_state.selected_column().unwrap_or(0);
+        let last = self.data.1.len().saturating_sub(1);
+        self.table_state.select_cell(Some((last, col)));
+    }
+
+    /// True if the database changed on disk since the last check. Starts
+    /// watching a database on the first check after it is opened.
+    fn db_changed(&mut self, app: &App) -> Result<bool, AppError> {
+        let Some(db) = &app.current_db else {
+            return Ok(false);
+        };
+        match &mut self.watcher {
+            Some(watcher) if watcher.path() == db.path => watcher.changed(),
+            _ => {
+                self.watcher = Some(app.watch()?);
+                Ok(false)
             }
         }
+    }
+
+    /// Show what changed on disk: read the tables again and reload the page
+    /// keeping the selection and the cursor, then highlight the rows that
+    /// changed. Counts are redone in the background, one at a time however
+    /// often the database changes, and the old ones are shown until then.
+    fn refresh(&mut self, app: &mut App) -> Result<(), AppError> {
+        let had_table = app
+            .current_db
+            .as_ref()
+            .is_some_and(|db| self.get_selected_table(db).is_some());
+        self.reload_tables(app)?;
+        let db = app.current_db.as_ref().ok_or(AppError::NoDatabase)?;
+        if self.get_selected_table(db).is_none() {
+            if had_table {
+                // the table shown is gone
+                return self.show_overview(app, db);
+            }
+            return Ok(());
+        }
+
+        let old_rows = std::mem::take(&mut self.data.1);
+        let old_keys = std::mem::take(&mut self.row_keys);
+        let (row, col) = self.table_state.selected_cell().unwrap_or((0, 0));
+        let row = self.go_to_row(self.offset + row);
+        self.load_table_data(app, db)?;
+        // columns may have been dropped on disk
+        let col = col.min(self.data.0.len().saturating_sub(1));
+        self.table_state.select_cell(Some((row, col)));
+        let changed = changed_rows(&old_rows, &old_keys, &self.data.1, &self.row_keys);
+        if !changed.is_empty() {
+            self.changed_rows = Some((Instant::now(), changed));
+        }
         Ok(())
     }
+
+    /// Collect results of background work, called regularly by the main loop.
+    pub fn tick(&mut self, app: &mut App) -> Result<(), AppError> {
+        if let Some(follow) = &self.follow {
+            if follow.counting.is_some() || follow.polled_at.elapsed() >= self.follow_interval {
+                self.poll_follow(app)?;
+            }
+        }
+        if self.watch && self.watched_at.elapsed() >= WATCH_INTERVAL {
+            self.watched_at = Instant::now();
+            if self.db_changed(app)? {
+                self.refresh(app)?;
+            }
+        }
+        if let Some(view) = &mut self.diff_view {
+            if let Err(e) = view.poll() {
+                self.diff_view = None;
+                return Err(e);
+            }
+        }
+        if let Some(wrote) = self.maintenance.as_mut().and_then(MaintenanceMenu::poll) {
+            if wrote? {
+                // rows may have moved and counts and sizes changed
+                self.needs_reload = true;
+                self.reload_overview(app)?;
+                self.pending_sizes = Some(app.table_sizes_in_background()?);
+                if let Some(db) = &app.current_db {
+                    self.recount(app, db)?;
+                }
+            }
+        }
+        if self.needs_reload {
+            self.needs_reload = false;
+            if let (Some(db), false) = (&app.current_db, self.data.0.is_empty()) {
+                // keep the cursor on the same row across the new page boundaries
+                let (row, col) = self.table_state.selected_cell().unwrap_or((0, 0));
+                let row = self.go_to_row(self.offset + row);
+                self.load_table_data(app, db)?;
+                self.table_state.select_cell(Some((row, col)));
+            }
+        }
+
+        if let Some(rx) = &self.pending_sizes {
+            match rx.try_recv() {
+                Ok(sizes) => {
+                    if let Some(overview) = &mut self.overview {
+                        overview.table_sizes = sizes;
+                    }
+                    self.pending_sizes = None;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.pending_sizes = None,
+            }
+        }
+
+        let mut finished = Vec::default();
+        self.pending_counts.retain(|(key, rx)| match rx.try_recv() {
+            Ok(res) => {
+                finished.push((key.clone(), res));
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => false,
+        });
+        if let Some(rx) = &self.overview_counts {
+            loop {
+                match rx.try_recv() {
+                    Ok(done) => finished.push(done),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.overview_counts = None;
+                        break;
+                    }
+                }
+            }
+        }
+        let current = self
+            .get_selected_table(app.current_db.as_ref().ok_or(AppError::NoDatabase)?)
+            .map(|table| app::count_sql(table, &self.filter(table)));
+        // one failed count doesn't lose the others
+        let mut failed = None;
+        for (key, res) in finished {
+            let count = match res {
+                Ok(count) => RowCount::Exact(count),
+                Err(e) => {
+                    failed.get_or_insert(e);
+                    continue;
+                }
+            };
+            if current.as_ref() == Some(&key) {
+                self.total_rows = Some(count);
+            }
+            self.row_counts.insert(key, count);
+        }
+        failed.map_or(Ok(()), Err)
+    }
 }
 
-fn map_to_row<'a>(widths: &mut Vec<usize>, index: usize, row_data: &[String]) -> Row<'a> {
+/// Positions in the new page of the rows that are new or differ from the
+/// old page, matched by rowid when the rows have one and by position
+/// otherwise.
+fn changed_rows(
+    old: &[Vec<Value>],
+    old_keys: &[i64],
+    new: &[Vec<Value>],
+    new_keys: &[i64],
+) -> Vec<usize> {
+    new.iter()
+        .enumerate()
+        .filter(|(i, row)| {
+            let before = match new_keys.get(*i) {
+                Some(key) => old_keys
+                    .iter()
+                    .position(|k| k == key)
+                    .and_then(|at| old.get(at)),
+                None => old.get(*i),
+            };
+            before != Some(*row)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn map_to_row<'a>(
+    columns: &Columns,
+    visible: &[usize],
+    index: usize,
+    values: &[Value],
+    texts: &[String],
+    changed: bool,
+) -> Row<'a> {
     let mut style = Style::new();
-    if index % 2 != 0 {
+    if changed {
+        style = style.bg(CHANGED_COLOR);
+    } else if !index.is_multiple_of(2) {
         style = style.bg(Color::Black);
     }
     Row::new(
-        row_data
+        visible
             .iter()
-            .enumerate()
-            .map(|(i, x)| map_to_cell_calc_width(widths, i, x))
+            .map(|c| {
+                let value = values.get(*c).unwrap_or(&Value::Null);
+                let text = texts.get(*c).map_or("", String::as_str);
+                map_to_cell(columns, *c, value, text)
+            })
             .collect::<Vec<Cell>>(),
     )
     .style(style)
 }
 
-fn map_to_cell_calc_width<'a>(widths: &mut Vec<usize>, index: usize, text: &String) -> Cell<'a> {
-    let value = Text::from(text.to_string());
-    if let Some(w) = widths.get_mut(index) {
-        *w = (*w).max(value.width())
-    } else {
-        widths.push(value.width());
-    }
-    Cell::from(value)
+/// Each storage class gets its own style, numbers are right aligned.
+fn map_to_cell<'a>(columns: &Columns, col: usize, value: &Value, text: &str) -> Cell<'a> {
+    let formatted = *value.display() != *text;
+    let text = Text::from(columns::truncate(text, columns.width(col)));
+    let text = match value {
+        _ if formatted => text.fg(FORMATTED_COLOR),
+        Value::Null => text.fg(NULL_COLOR).italic(),
+        Value::Integer(_) | Value::Real(_) => text.fg(NUMBER_COLOR).right_aligned(),
+        Value::Blob(_) => text.fg(BLOB_COLOR),
+        Value::Text(_) => text,
+    };
+    Cell::from(text)
 }
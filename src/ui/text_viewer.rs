@@ -0,0 +1,481 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph},
+    Frame,
+};
+use serde_json::Value as Json;
+use std::collections::HashSet;
+use unicode_width::UnicodeWidthChar;
+
+use super::{
+    HIGHLIGHTED_COLOR, NULL_COLOR, NUMBER_COLOR, PRIMARY_COLOR, SECONDARY_COLOR, TEXT_COLOR,
+};
+
+const JSON_KEYS: &str = " Move [k/j] Scroll [h/l] Fold [Space] Fold/Unfold All [-/+] Yank Value [y] Yank Path [p] Close [Esc] ";
+const TEXT_KEYS: &str = " Scroll [k/j] Page [u/d] Top/Bottom [g/G] Yank [y] Close [Esc] ";
+const INDENT: usize = 2;
+const SCROLL_STEP: usize = 4;
+
+/// What the user did in the text viewer.
+pub enum ViewerAction {
+    None,
+    Close,
+    /// Copy the given text to the clipboard
+    Yank(String),
+}
+
+/// One step of the path to a JSON value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+type JsonPath = Vec<Segment>;
+
+/// A line of pretty printed JSON and the value it belongs to.
+struct JsonLine {
+    depth: usize,
+    path: JsonPath,
+    spans: Vec<Span<'static>>,
+    /// True for lines opening or closing an object or array
+    foldable: bool,
+}
+
+enum Content {
+    Json {
+        root: Json,
+        folded: HashSet<JsonPath>,
+        lines: Vec<JsonLine>,
+        /// Line the cursor is on
+        cursor: usize,
+        /// Columns scrolled to the right
+        column: usize,
+    },
+    Text(String),
+}
+
+/// Full screen viewer for text cells, JSON is pretty printed and can be
+/// folded, anything else is wrapped and scrollable.
+pub struct TextViewer {
+    title: String,
+    content: Content,
+    /// First line shown
+    scroll: usize,
+    /// Lines that fit on screen, known after drawing
+    height: usize,
+    /// Number of lines after wrapping, known after drawing
+    line_count: usize,
+}
+
+impl TextViewer {
+    pub fn new(column: &str, text: &str) -> Self {
+        let trimmed = text.trim_start();
+        let json = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            serde_json::from_str::<Json>(text).ok()
+        } else {
+            None
+        };
+        let (title, content) = match json {
+            Some(root) => {
+                let lines = json_lines(&root, &HashSet::new());
+                let content = Content::Json {
+                    root,
+                    folded: HashSet::new(),
+                    lines,
+                    cursor: 0,
+                    column: 0,
+                };
+                (format!(" {column} - JSON "), content)
+            }
+            None => (
+                format!(" {column} - {} characters ", text.chars().count()),
+                Content::Text(text.to_string()),
+            ),
+        };
+        Self {
+            title,
+            content,
+            scroll: 0,
+            height: 0,
+            line_count: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: &KeyEvent) -> ViewerAction {
+        if key.code == KeyCode::Esc || key.code == KeyCode::Char('q') {
+            return ViewerAction::Close;
+        }
+        let page = (self.height / 2).max(1);
+        match &mut self.content {
+            Content::Text(text) => {
+                let last = self.line_count.saturating_sub(self.height);
+                match key.code {
+                    KeyCode::Char('j') => self.scroll = (self.scroll + 1).min(last),
+                    KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+                    KeyCode::Char('d') => self.scroll = (self.scroll + page).min(last),
+                    KeyCode::Char('u') => self.scroll = self.scroll.saturating_sub(page),
+                    KeyCode::Char('g') => self.scroll = 0,
+                    KeyCode::Char('G') => self.scroll = last,
+                    KeyCode::Char('y') => return ViewerAction::Yank(text.clone()),
+                    _ => {}
+                }
+            }
+            Content::Json {
+                root,
+                folded,
+                lines,
+                cursor,
+                column,
+            } => {
+                let last = lines.len().saturating_sub(1);
+                match key.code {
+                    KeyCode::Char('j') => *cursor = (*cursor + 1).min(last),
+                    KeyCode::Char('k') => *cursor = cursor.saturating_sub(1),
+                    KeyCode::Char('d') => *cursor = (*cursor + page).min(last),
+                    KeyCode::Char('u') => *cursor = cursor.saturating_sub(page),
+                    KeyCode::Char('g') => *cursor = 0,
+                    KeyCode::Char('G') => *cursor = last,
+                    KeyCode::Char('l') => *column += SCROLL_STEP,
+                    KeyCode::Char('h') => *column = column.saturating_sub(SCROLL_STEP),
+                    KeyCode::Char(' ') | KeyCode::Enter => {
+                        let Some(line) = lines.get(*cursor) else {
+                            return ViewerAction::None;
+                        };
+                        if !line.foldable {
+                            return ViewerAction::None;
+                        }
+                        let path = line.path.clone();
+                        if !folded.remove(&path) {
+                            folded.insert(path.clone());
+                        }
+                        *lines = json_lines(root, folded);
+                        // folding from the closing bracket leaves the cursor past the end
+                        *cursor = lines.iter().position(|l| l.path == path).unwrap_or(0);
+                    }
+                    KeyCode::Char('-') => {
+                        *folded = containers(root, Vec::new())
+                            .into_iter()
+                            .filter(|p| !p.is_empty())
+                            .collect();
+                        *lines = json_lines(root, folded);
+                        *cursor = 0;
+                    }
+                    KeyCode::Char('+') | KeyCode::Char('=') => {
+                        folded.clear();
+                        let path = lines.get(*cursor).map(|l| l.path.clone());
+                        *lines = json_lines(root, folded);
+                        *cursor = lines
+                            .iter()
+                            .position(|l| Some(&l.path) == path.as_ref())
+                            .unwrap_or(0);
+                    }
+                    KeyCode::Char('y') => {
+                        if let Some(value) = lines.get(*cursor).and_then(|l| lookup(root, &l.path))
+                        {
+                            let text = match value {
+                                Json::String(s) => s.clone(),
+                                _ => serde_json::to_string_pretty(value).unwrap_or_default(),
+                            };
+                            return ViewerAction::Yank(text);
+                        }
+                    }
+                    KeyCode::Char('p') => {
+                        if let Some(line) = lines.get(*cursor) {
+                            return ViewerAction::Yank(path_string(&line.path));
+                        }
+                    }
+                    _ => {}
+                }
+                // keep the cursor on screen
+                if *cursor < self.scroll {
+                    self.scroll = *cursor;
+                } else if self.height > 0 && *cursor >= self.scroll + self.height {
+                    self.scroll = *cursor + 1 - self.height;
+                }
+            }
+        }
+        ViewerAction::None
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, lay: Rect) {
+        let (keys, path) = match &self.content {
+            Content::Json { lines, cursor, .. } => (
+                JSON_KEYS,
+                lines
+                    .get(*cursor)
+                    .map(|l| format!(" {} ", path_string(&l.path))),
+            ),
+            Content::Text(_) => (TEXT_KEYS, None),
+        };
+        let mut block = Block::bordered()
+            .title(
+                Line::from(self.title.as_str())
+                    .fg(SECONDARY_COLOR)
+                    .bold()
+                    .centered(),
+            )
+            .title_bottom(Line::from(keys).fg(SECONDARY_COLOR).centered())
+            .fg(PRIMARY_COLOR)
+            .border_type(BorderType::Rounded);
+        if let Some(path) = path {
+            block = block.title(Line::from(path).fg(TEXT_COLOR).left_aligned());
+        }
+        let [inner] = Layout::vertical([Constraint::Fill(1)])
+            .horizontal_margin(1)
+            .areas(block.inner(lay));
+        self.height = inner.height as usize;
+
+        let paragraph = match &self.content {
+            Content::Text(text) => {
+                let wrapped = wrap(text, inner.width as usize);
+                self.line_count = wrapped.len();
+                self.scroll = self.scroll.min(self.line_count.saturating_sub(self.height));
+                let lines: Vec<Line> = wrapped
+                    .into_iter()
+                    .skip(self.scroll)
+                    .take(self.height)
+                    .map(|l| Line::from(l).fg(TEXT_COLOR))
+                    .collect();
+                Paragraph::new(lines)
+            }
+            Content::Json {
+                lines,
+                cursor,
+                column,
+                ..
+            } => {
+                self.line_count = lines.len();
+                let shown: Vec<Line> = lines
+                    .iter()
+                    .enumerate()
+                    .skip(self.scroll)
+                    .take(self.height)
+                    .map(|(i, l)| {
+                        let mut spans = vec![Span::from(" ".repeat(l.depth * INDENT))];
+                        spans.extend(l.spans.iter().cloned());
+                        let line = Line::from(spans);
+                        if i == *cursor {
+                            line.style(Style::new().reversed())
+                        } else {
+                            line
+                        }
+                    })
+                    .collect();
+                Paragraph::new(shown).scroll((0, *column as u16))
+            }
+        };
+
+        frame.render_widget(Clear, lay);
+        frame.render_widget(block, lay);
+        frame.render_widget(paragraph, inner);
+    }
+}
+
+/// Pretty print a JSON value into lines, leaving folded containers closed.
+fn json_lines(root: &Json, folded: &HashSet<JsonPath>) -> Vec<JsonLine> {
+    let mut lines = Vec::new();
+    push_value(root, Vec::new(), None, 0, true, folded, &mut lines);
+    lines
+}
+
+fn push_value(
+    value: &Json,
+    path: JsonPath,
+    key: Option<&str>,
+    depth: usize,
+    last: bool,
+    folded: &HashSet<JsonPath>,
+    lines: &mut Vec<JsonLine>,
+) {
+    let mut spans = Vec::new();
+    if let Some(key) = key {
+        spans.push(Span::from(quote(key)).fg(SECONDARY_COLOR));
+        spans.push(Span::from(": ").fg(TEXT_COLOR));
+    }
+    let comma = if last { "" } else { "," };
+
+    let (open, close, len, unit) = match value {
+        Json::Object(map) => ("{", "}", map.len(), "keys"),
+        Json::Array(items) => ("[", "]", items.len(), "items"),
+        scalar => {
+            spans.push(scalar_span(scalar));
+            spans.push(Span::from(comma).fg(TEXT_COLOR));
+            lines.push(JsonLine {
+                depth,
+                path,
+                spans,
+                foldable: false,
+            });
+            return;
+        }
+    };
+
+    if len == 0 {
+        spans.push(Span::from(format!("{open}{close}{comma}")).fg(TEXT_COLOR));
+        lines.push(JsonLine {
+            depth,
+            path,
+            spans,
+            foldable: false,
+        });
+        return;
+    }
+    if folded.contains(&path) {
+        spans.push(Span::from(format!("{open}…{close}{comma}")).fg(TEXT_COLOR));
+        spans.push(Span::from(format!(" {len} {unit}")).fg(NULL_COLOR));
+        lines.push(JsonLine {
+            depth,
+            path,
+            spans,
+            foldable: true,
+        });
+        return;
+    }
+
+    spans.push(Span::from(open).fg(TEXT_COLOR));
+    lines.push(JsonLine {
+        depth,
+        path: path.clone(),
+        spans,
+        foldable: true,
+    });
+    match value {
+        Json::Object(map) => {
+            for (i, (k, v)) in map.iter().enumerate() {
+                let mut child = path.clone();
+                child.push(Segment::Key(k.clone()));
+                push_value(v, child, Some(k), depth + 1, i + 1 == len, folded, lines);
+            }
+        }
+        Json::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                let mut child = path.clone();
+                child.push(Segment::Index(i));
+                push_value(v, child, None, depth + 1, i + 1 == len, folded, lines);
+            }
+        }
+        _ => {}
+    }
+    lines.push(JsonLine {
+        depth,
+        path,
+        spans: vec![Span::from(format!("{close}{comma}")).fg(TEXT_COLOR)],
+        foldable: true,
+    });
+}
+
+fn scalar_span(value: &Json) -> Span<'static> {
+    let text = value.to_string();
+    match value {
+        Json::String(_) => Span::from(text).fg(HIGHLIGHTED_COLOR),
+        Json::Number(_) => Span::from(text).fg(NUMBER_COLOR),
+        _ => Span::from(text).fg(NULL_COLOR).italic(),
+    }
+}
+
+/// Paths of every non empty object and array in the value.
+fn containers(value: &Json, path: JsonPath) -> Vec<JsonPath> {
+    let children: Vec<(Segment, &Json)> = match value {
+        Json::Object(map) => map
+            .iter()
+            .map(|(k, v)| (Segment::Key(k.clone()), v))
+            .collect(),
+        Json::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (Segment::Index(i), v))
+            .collect(),
+        _ => return Vec::new(),
+    };
+    if children.is_empty() {
+        return Vec::new();
+    }
+    let mut paths = vec![path.clone()];
+    for (segment, child) in children {
+        let mut child_path = path.clone();
+        child_path.push(segment);
+        paths.extend(containers(child, child_path));
+    }
+    paths
+}
+
+fn lookup<'a>(root: &'a Json, path: &[Segment]) -> Option<&'a Json> {
+    path.iter().try_fold(root, |value, segment| match segment {
+        Segment::Key(k) => value.get(k),
+        Segment::Index(i) => value.get(i),
+    })
+}
+
+/// The path in the syntax of sqlite's json functions, e.g. `$.tags[0]`.
+fn path_string(path: &[Segment]) -> String {
+    let mut out = String::from("$");
+    for segment in path {
+        match segment {
+            Segment::Key(k)
+                if !k.is_empty() && k.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                out.push('.');
+                out.push_str(k);
+            }
+            Segment::Key(k) => {
+                out.push('.');
+                out.push_str(&quote(k));
+            }
+            Segment::Index(i) => out.push_str(&format!("[{i}]")),
+        }
+    }
+    out
+}
+
+fn quote(key: &str) -> String {
+    Json::String(key.to_string()).to_string()
+}
+
+/// Break text into lines no wider than the given width.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for c in raw.chars() {
+            let c = if c == '\t' { ' ' } else { c };
+            let w = c.width().unwrap_or(0);
+            if used + w > width {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            line.push(c);
+            used += w;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_numbers_as_written() {
+        let numbers = [
+            "12345678901234567890123",
+            "0.1000000000000000055511151231257827",
+            "1e400",
+            "1.50",
+        ];
+        let root: Json = serde_json::from_str(&format!("[{}]", numbers.join(", "))).unwrap();
+        let shown: Vec<String> = root
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| scalar_span(n).content.into_owned())
+            .collect();
+        assert_eq!(shown, numbers);
+    }
+}
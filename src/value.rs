@@ -0,0 +1,114 @@
+use rusqlite::types::{ToSql, ToSqlOutput, ValueRef};
+use std::borrow::Cow;
+
+/// A single value read from the database, keeping its sqlite storage class.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    /// Text as stored, which is not always valid UTF-8
+    Text(Vec<u8>),
+    Blob(Vec<u8>),
+}
+
+impl Value {
+    /// The value as shown in the table, invalid UTF-8 is escaped.
+    pub fn display(&self) -> Cow<'_, str> {
+        match self {
+            Value::Null => Cow::Borrowed("NULL"),
+            Value::Integer(v) => Cow::Owned(v.to_string()),
+            Value::Real(v) => Cow::Owned(format_real(*v)),
+            Value::Text(bytes) => match std::str::from_utf8(bytes) {
+                Ok(s) => Cow::Borrowed(s),
+                Err(_) => Cow::Owned(escape_invalid_utf8(bytes)),
+            },
+            Value::Blob(bytes) => Cow::Owned(format!("<blob {} bytes>", bytes.len())),
+        }
+    }
+}
+
+impl Value {
+    /// The value as a sql literal, for generated statements. Text that is
+    /// not valid UTF-8 is written as a blob cast to text.
+    pub fn sql_literal(&self) -> String {
+        match self {
+            Value::Null => "NULL".to_string(),
+            Value::Integer(v) => v.to_string(),
+            Value::Real(v) if v.is_nan() => "NULL".to_string(),
+            // sqlite reads reals too large to hold as infinity
+            Value::Real(v) if v.is_infinite() => {
+                if *v > 0.0 { "9e999" } else { "-9e999" }.to_string()
+            }
+            Value::Real(v) => format!("{v:?}"),
+            Value::Text(bytes) => match std::str::from_utf8(bytes) {
+                Ok(s) => format!("'{}'", s.replace('\'', "''")),
+                Err(_) => format!("CAST({} AS TEXT)", hex_literal(bytes)),
+            },
+            Value::Blob(bytes) => hex_literal(bytes),
+        }
+    }
+
+    /// Read back a value the user edited as text, keeping the storage class
+    /// of the original where the text still fits it.
+    pub fn from_edited(text: &str, original: &Value) -> Value {
+        let parsed = match original {
+            Value::Integer(_) => text.trim().parse().ok().map(Value::Integer),
+            Value::Real(_) => text.trim().parse().ok().map(Value::Real),
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| Value::Text(text.as_bytes().to_vec()))
+    }
+}
+
+impl ToSql for Value {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(match self {
+            Value::Null => ValueRef::Null,
+            Value::Integer(v) => ValueRef::Integer(*v),
+            Value::Real(v) => ValueRef::Real(*v),
+            Value::Text(bytes) => ValueRef::Text(bytes),
+            Value::Blob(bytes) => ValueRef::Blob(bytes),
+        }))
+    }
+}
+
+impl From<ValueRef<'_>> for Value {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Null => Value::Null,
+            ValueRef::Integer(v) => Value::Integer(v),
+            ValueRef::Real(v) => Value::Real(v),
+            ValueRef::Text(s) => Value::Text(s.to_vec()),
+            ValueRef::Blob(b) => Value::Blob(b.to_vec()),
+        }
+    }
+}
+
+/// Reals keep a decimal point like the sqlite shell shows them, so `1.0`
+/// can be told apart from the integer `1`.
+fn format_real(v: f64) -> String {
+    if v.is_finite() && v.fract() == 0.0 && v.abs() < 1e15 {
+        format!("{v:.1}")
+    } else {
+        v.to_string()
+    }
+}
+
+/// Decode as much UTF-8 as possible, showing invalid bytes as `\xNN`.
+fn escape_invalid_utf8(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        out.push_str(chunk.valid());
+        for b in chunk.invalid() {
+            out.push_str(&format!("\\x{b:02x}"));
+        }
+    }
+    out
+}
+
+/// Bytes as a blob literal, e.g. `X'00ff'`.
+fn hex_literal(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("X'{hex}'")
+}